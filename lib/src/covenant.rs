@@ -0,0 +1,178 @@
+#![allow(dead_code)]
+
+use crate::{
+    signature::schnorr::{schnorr_verify, SignFlag},
+    taproot::P2TR,
+    tx::{
+        sighash::{key_path_sighash, SighashError},
+        transaction::{Transaction, TxIn, TxOut, SEQUENCE_FINAL},
+    },
+    txo::{
+        connector::Connector,
+        payload::DecodedPayload,
+        projector::{Projector, ProjectorTag},
+        vtxo::VTXO,
+    },
+    valtype::outpoint::Outpoint,
+};
+use musig2::{secp::Scalar, secp256k1, KeyAggContext};
+
+// Dust value carried by each connector, virtual or bare.
+pub const CONNECTOR_VALUE: u64 = 450;
+
+const COVENANT_TX_VERSION: u32 = 2;
+
+#[derive(Debug, PartialEq)]
+pub enum CovenantError {
+    ProjectorTagMismatch,
+    ValueOverflow,
+    KeyError(secp256k1::Error),
+    SighashError(SighashError),
+    InvalidSignature,
+}
+
+// Presigned fan-out from a projector output to its VTXOs or connectors.
+// msg.senders[] and the operator sign it through the projector reveal path (key path) before the
+// pool transaction is broadcast, constraining the projector to exactly these outputs.
+pub struct Covenant {
+    projector: Projector,
+    projector_outpoint: Outpoint,
+    outputs: Vec<TxOut>,
+}
+
+impl Covenant {
+    // Fan out the VTXO projector into VTXOs of the given values.
+    pub fn vtxos(
+        projector: Projector,
+        projector_outpoint: Outpoint,
+        vtxos: &[(VTXO, u64)],
+    ) -> Result<Covenant, CovenantError> {
+        if !matches!(projector.tag(), ProjectorTag::VTXOProjector) {
+            return Err(CovenantError::ProjectorTagMismatch);
+        }
+
+        let mut outputs = Vec::<TxOut>::new();
+        for (vtxo, value) in vtxos {
+            let spk = vtxo.spk().map_err(CovenantError::KeyError)?;
+            outputs.push(TxOut::new(*value, spk));
+        }
+
+        Covenant::new(projector, projector_outpoint, outputs)
+    }
+
+    // Fan out the connector projector into dust connectors.
+    pub fn connectors(
+        projector: Projector,
+        projector_outpoint: Outpoint,
+        connectors: &[Connector],
+    ) -> Result<Covenant, CovenantError> {
+        if !matches!(projector.tag(), ProjectorTag::ConnectorProjector) {
+            return Err(CovenantError::ProjectorTagMismatch);
+        }
+
+        let mut outputs = Vec::<TxOut>::new();
+        for connector in connectors {
+            let spk = connector.spk().map_err(CovenantError::KeyError)?;
+            outputs.push(TxOut::new(CONNECTOR_VALUE, spk));
+        }
+
+        Covenant::new(projector, projector_outpoint, outputs)
+    }
+
+    fn new(
+        projector: Projector,
+        projector_outpoint: Outpoint,
+        outputs: Vec<TxOut>,
+    ) -> Result<Covenant, CovenantError> {
+        let covenant = Covenant {
+            projector,
+            projector_outpoint,
+            outputs,
+        };
+
+        // The projector output must be able to carry the fan-out.
+        covenant.value()?;
+
+        Ok(covenant)
+    }
+
+    pub fn projector(&self) -> Projector {
+        self.projector.clone()
+    }
+
+    pub fn projector_outpoint(&self) -> Outpoint {
+        self.projector_outpoint
+    }
+
+    // Value the projector output must carry: the sum of the fan-out outputs, with no fee.
+    pub fn value(&self) -> Result<u64, CovenantError> {
+        self.outputs
+            .iter()
+            .try_fold(0u64, |total, output| total.checked_add(output.value()))
+            .ok_or(CovenantError::ValueOverflow)
+    }
+
+    pub fn transaction(&self) -> Transaction {
+        let input = TxIn::new(self.projector_outpoint, SEQUENCE_FINAL);
+
+        Transaction::new(COVENANT_TX_VERSION, vec![input], self.outputs.clone(), 0)
+    }
+
+    // Key aggregation context of msg.senders[] and the operator, tweaked to the projector output key.
+    // MuSig2 sessions signing the covenant run on this context.
+    pub fn key_agg_ctx(&self) -> Result<KeyAggContext, CovenantError> {
+        let taproot = self.projector.taproot().map_err(CovenantError::KeyError)?;
+        let tap_tweak = Scalar::from_slice(&taproot.tap_tweak())
+            .map_err(|_| CovenantError::KeyError(secp256k1::Error::InvalidTweak))?;
+
+        self.projector
+            .key_agg_ctx()
+            .map_err(CovenantError::KeyError)?
+            .with_xonly_tweak(tap_tweak)
+            .map_err(|_| CovenantError::KeyError(secp256k1::Error::InvalidTweak))
+    }
+
+    // BIP-341 key path sighash of the fan-out transaction.
+    pub fn sighash(&self) -> Result<[u8; 32], CovenantError> {
+        let prevout = TxOut::new(
+            self.value()?,
+            self.projector.spk().map_err(CovenantError::KeyError)?,
+        );
+
+        key_path_sighash(&self.transaction(), 0, &[prevout]).map_err(CovenantError::SighashError)
+    }
+
+    // Verify an aggregate signature against the projector output key.
+    pub fn verify(&self, signature: [u8; 64]) -> Result<(), CovenantError> {
+        let output_key = self
+            .projector
+            .taproot()
+            .and_then(|taproot| taproot.tweaked_key_x_only())
+            .map_err(CovenantError::KeyError)?;
+
+        schnorr_verify(
+            output_key.serialize(),
+            self.sighash()?,
+            signature,
+            SignFlag::BIP340Sign,
+        )
+        .map_err(|_| CovenantError::InvalidSignature)
+    }
+}
+
+// Value the connector projector output must carry to fan out into the given number of connectors.
+pub fn connector_projector_value(num_connectors: usize) -> Result<u64, CovenantError> {
+    CONNECTOR_VALUE
+        .checked_mul(num_connectors as u64)
+        .ok_or(CovenantError::ValueOverflow)
+}
+
+// Verify the projector signatures a payload carries against the VTXO and connector covenants.
+pub fn verify_payload(
+    payload: &DecodedPayload,
+    vtxo_covenant: &Covenant,
+    connector_covenant: &Covenant,
+) -> Result<(), CovenantError> {
+    vtxo_covenant.verify(payload.vtxo_projector_agg_sig())?;
+    connector_covenant.verify(payload.connector_projector_agg_sig())
+}
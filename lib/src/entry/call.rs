@@ -0,0 +1,261 @@
+#![allow(dead_code)]
+
+use super::entry::VERSION_BIT;
+use crate::{
+    serialization::{
+        bits::{BitReader, BitWriter},
+        cpe::{CPEContext, CPEDecodingError, CompactPayloadDecoding, CompactPayloadEncoding},
+        prefix::{to_compact_size, Prefix},
+        serialize::{write_all, ByteReader, Serialize, SerializeError},
+    },
+    valtype::{account::Account, contract::Contract, maybe_common::MaybeCommon, value::ShortVal},
+};
+use std::io::{Read, Write};
+
+type Bytes = Vec<u8>;
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Call {
+    caller: Account,
+    nonce: u32,
+    contract: MaybeCommon<Contract>,
+    method: u8,
+    args: Vec<Bytes>,
+    value: MaybeCommon<ShortVal>,
+}
+
+impl Call {
+    pub fn new(
+        caller: Account,
+        nonce: u32,
+        contract: MaybeCommon<Contract>,
+        method: u8,
+        args: Vec<Bytes>,
+        value: MaybeCommon<ShortVal>,
+    ) -> Call {
+        Call {
+            caller,
+            nonce,
+            contract,
+            method,
+            args,
+            value,
+        }
+    }
+
+    pub fn new_uncommon(
+        caller: Account,
+        nonce: u32,
+        contract: Contract,
+        method: u8,
+        args: Vec<Bytes>,
+        value: ShortVal,
+    ) -> Call {
+        Call {
+            caller,
+            nonce,
+            contract: MaybeCommon::Uncommon(contract),
+            method,
+            args,
+            value: MaybeCommon::Uncommon(value),
+        }
+    }
+
+    pub fn caller(&self) -> Account {
+        self.caller
+    }
+
+    // Sequence number of the caller
+    pub fn nonce(&self) -> u32 {
+        self.nonce
+    }
+
+    pub fn contract(&self) -> MaybeCommon<Contract> {
+        self.contract
+    }
+
+    // Method selector
+    pub fn method(&self) -> u8 {
+        self.method
+    }
+
+    pub fn args(&self) -> Vec<Bytes> {
+        self.args.clone()
+    }
+
+    // Sats attached to the call
+    pub fn value(&self) -> MaybeCommon<ShortVal> {
+        self.value
+    }
+
+    pub fn set_caller_account_index(&mut self, account_index: u32) {
+        self.caller.set_account_index(account_index);
+    }
+
+    pub fn set_contract_index(&mut self, contract_index: u32) {
+        match &mut self.contract {
+            MaybeCommon::Uncommon(contract) => {
+                contract.set_contract_index(contract_index);
+            }
+            MaybeCommon::Common(contract, _) => {
+                contract.set_contract_index(contract_index);
+            }
+        }
+    }
+
+    pub fn set_contract_common_index(&mut self, common_index: u8) {
+        self.contract = MaybeCommon::Common(self.contract.value(), common_index);
+    }
+
+    pub fn set_value_common_index(&mut self, common_index: u8) {
+        self.value = MaybeCommon::Common(self.value.value(), common_index);
+    }
+}
+
+impl CompactPayloadEncoding for Call {
+    fn write_cpe(&self, writer: &mut BitWriter) {
+        // Transfer or call
+        writer.write_bit(false);
+
+        // Call
+        writer.write_bit(true);
+
+        // Caller
+        self.caller.write_cpe(writer);
+
+        // Nonce
+        ShortVal(self.nonce).write_cpe(writer);
+
+        // Contract
+        self.contract.write_cpe(writer);
+
+        // Method
+        writer.write_uint(self.method as u64, 8);
+
+        // Args: count, then each arg length-prefixed
+        ShortVal(self.args.len() as u32).write_cpe(writer);
+        for arg in &self.args {
+            ShortVal(arg.len() as u32).write_cpe(writer);
+            writer.write_bytes(arg);
+        }
+
+        // Value
+        self.value.write_cpe(writer);
+    }
+}
+
+impl CompactPayloadDecoding for Call {
+    fn from_cpe(
+        reader: &mut BitReader,
+        context: &impl CPEContext,
+    ) -> Result<Call, CPEDecodingError> {
+        // Transfer or call
+        if reader.read_bit()? {
+            return Err(CPEDecodingError::EntryTypeError);
+        }
+
+        // Call
+        if !reader.read_bit()? {
+            return Err(CPEDecodingError::EntryTypeError);
+        }
+
+        // Caller
+        let caller = Account::from_cpe(reader, context)?;
+
+        // Nonce
+        let nonce = ShortVal::from_cpe(reader, context)?.value();
+
+        // Contract
+        let contract = MaybeCommon::<Contract>::from_cpe(reader, context)?;
+
+        // Method
+        let method = reader.read_uint(8)? as u8;
+
+        // Args
+        let num_args = ShortVal::from_cpe(reader, context)?.value();
+        let mut args = Vec::<Bytes>::new();
+        for _ in 0..num_args {
+            let arg_len = ShortVal::from_cpe(reader, context)?.value() as usize;
+
+            // Each byte takes 8 bits; reject lengths the remaining bits cannot hold.
+            if arg_len > reader.remaining() / 8 {
+                return Err(CPEDecodingError::EndOfBitsError);
+            }
+
+            args.push(reader.read_bytes(arg_len)?);
+        }
+
+        // Value
+        let value = MaybeCommon::<ShortVal>::from_cpe(reader, context)?;
+
+        Ok(Call::new(caller, nonce, contract, method, args, value))
+    }
+}
+
+impl Serialize for Call {
+    fn write_to<W: Write>(&self, writer: &mut W) -> Result<(), SerializeError> {
+        // Entry type: 0x01 with the version bit
+        write_all(writer, &[0x01 | VERSION_BIT])?;
+
+        // Caller
+        write_all(writer, &self.caller.key().serialize())?;
+
+        // Nonce
+        write_all(writer, &self.nonce.to_le_bytes())?;
+
+        // Contract
+        write_all(writer, &self.contract.value().contract_id())?;
+
+        // Method
+        write_all(writer, &[self.method])?;
+
+        // Args: CompactSize count, then each arg CompactSize-prefixed
+        write_all(writer, &to_compact_size(self.args.len() as u64))?;
+        for arg in &self.args {
+            write_all(writer, &arg.prefix_compact_size())?;
+        }
+
+        // Value
+        write_all(writer, &self.value.value().value().to_le_bytes())
+    }
+
+    fn read_from<R: Read>(reader: &mut ByteReader<R>) -> Result<Call, SerializeError> {
+        // Entry type: 0x01, with the version bit unless predating nonces
+        let offset = reader.offset();
+        let entry_type = reader.read_u8()?;
+        if entry_type & !VERSION_BIT != 0x01 {
+            return Err(SerializeError::EntryTypeError { offset, entry_type });
+        }
+        let with_nonce = entry_type & VERSION_BIT != 0;
+
+        // Caller
+        let caller = Account::new(reader.read_key()?);
+
+        // Nonce, zero for entries predating nonces
+        let nonce = match with_nonce {
+            true => reader.read_u32_le()?,
+            false => 0,
+        };
+
+        // Contract
+        let contract = Contract::new(reader.read_array::<32>()?);
+
+        // Method
+        let method = reader.read_u8()?;
+
+        // Args
+        let num_args = reader.read_compact_size()?;
+        let mut args = Vec::<Bytes>::new();
+        for _ in 0..num_args {
+            args.push(reader.read_compact_size_prefixed()?);
+        }
+
+        // Value
+        let value = ShortVal::new(reader.read_u32_le()?);
+
+        Ok(Call::new_uncommon(
+            caller, nonce, contract, method, args, value,
+        ))
+    }
+}
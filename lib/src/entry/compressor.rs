@@ -0,0 +1,462 @@
+#![allow(dead_code)]
+
+use super::{call::Call, entry::Entry, transfer::Transfer};
+use crate::{
+    serialization::{
+        bits::{BitReader, BitWriter},
+        cpe::{CPEContext, CPEDecodingError, CompactPayloadDecoding, CompactPayloadEncoding},
+    },
+    valtype::{
+        account::Account,
+        contract::Contract,
+        maybe_common::MaybeCommon,
+        value::{LongVal, ShortVal},
+    },
+};
+use musig2::secp256k1::XOnlyPublicKey;
+
+type Key = XOnlyPublicKey;
+
+// 3-bit common index
+pub const COMMON_TABLE_SIZE: usize = 8;
+
+// Bits a common reference costs in place of the uncommon encoding (the common bit is paid either way).
+const COMMON_INDEX_BITS: usize = 3;
+
+// 4-bit table length (0..=8)
+const COMMON_TABLE_LEN_BITS: usize = 4;
+
+// Common accounts, contracts, short values and long values referenced by 3-bit common indexes.
+// The tables travel alongside the entries and resolve common references during decoding.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CommonTables {
+    accounts: Vec<Account>,
+    contracts: Vec<Contract>,
+    short_vals: Vec<ShortVal>,
+    long_vals: Vec<LongVal>,
+}
+
+impl CommonTables {
+    pub fn new(
+        accounts: Vec<Account>,
+        contracts: Vec<Contract>,
+        short_vals: Vec<ShortVal>,
+        long_vals: Vec<LongVal>,
+    ) -> Option<CommonTables> {
+        if accounts.len() > COMMON_TABLE_SIZE
+            || contracts.len() > COMMON_TABLE_SIZE
+            || short_vals.len() > COMMON_TABLE_SIZE
+            || long_vals.len() > COMMON_TABLE_SIZE
+        {
+            return None;
+        }
+
+        Some(CommonTables {
+            accounts,
+            contracts,
+            short_vals,
+            long_vals,
+        })
+    }
+
+    pub fn accounts(&self) -> Vec<Account> {
+        self.accounts.clone()
+    }
+
+    pub fn contracts(&self) -> Vec<Contract> {
+        self.contracts.clone()
+    }
+
+    pub fn short_vals(&self) -> Vec<ShortVal> {
+        self.short_vals.clone()
+    }
+
+    pub fn long_vals(&self) -> Vec<LongVal> {
+        self.long_vals.clone()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+            && self.contracts.is_empty()
+            && self.short_vals.is_empty()
+            && self.long_vals.is_empty()
+    }
+
+    // Resolve common references from the tables and everything else from the given context.
+    pub fn with_context<'a, C: CPEContext>(&'a self, context: &'a C) -> CommonTablesContext<'a, C> {
+        CommonTablesContext {
+            tables: self,
+            context,
+        }
+    }
+
+    fn account_index_of(&self, key: Key) -> Option<u8> {
+        self.accounts
+            .iter()
+            .position(|account| account.key() == key)
+            .map(|index| index as u8)
+    }
+
+    fn contract_index_of(&self, contract_id: [u8; 32]) -> Option<u8> {
+        self.contracts
+            .iter()
+            .position(|contract| contract.contract_id() == contract_id)
+            .map(|index| index as u8)
+    }
+
+    fn short_val_index_of(&self, value: u32) -> Option<u8> {
+        self.short_vals
+            .iter()
+            .position(|short_val| short_val.value() == value)
+            .map(|index| index as u8)
+    }
+
+    fn long_val_index_of(&self, value: u64) -> Option<u8> {
+        self.long_vals
+            .iter()
+            .position(|long_val| long_val.value() == value)
+            .map(|index| index as u8)
+    }
+}
+
+// Each table is encoded as its 4-bit length followed by its values, accounts first.
+impl CompactPayloadEncoding for CommonTables {
+    fn write_cpe(&self, writer: &mut BitWriter) {
+        // Accounts
+        writer.write_uint(self.accounts.len() as u64, COMMON_TABLE_LEN_BITS);
+        for account in self.accounts.iter() {
+            account.write_cpe(writer);
+        }
+
+        // Contracts
+        writer.write_uint(self.contracts.len() as u64, COMMON_TABLE_LEN_BITS);
+        for contract in self.contracts.iter() {
+            contract.write_cpe(writer);
+        }
+
+        // Short values
+        writer.write_uint(self.short_vals.len() as u64, COMMON_TABLE_LEN_BITS);
+        for short_val in self.short_vals.iter() {
+            short_val.write_cpe(writer);
+        }
+
+        // Long values
+        writer.write_uint(self.long_vals.len() as u64, COMMON_TABLE_LEN_BITS);
+        for long_val in self.long_vals.iter() {
+            long_val.write_cpe(writer);
+        }
+    }
+}
+
+impl CompactPayloadDecoding for CommonTables {
+    fn from_cpe(
+        reader: &mut BitReader,
+        context: &impl CPEContext,
+    ) -> Result<CommonTables, CPEDecodingError> {
+        // Accounts
+        let mut accounts = Vec::<Account>::new();
+        for _ in 0..read_table_len(reader)? {
+            accounts.push(Account::from_cpe(reader, context)?);
+        }
+
+        // Contracts
+        let mut contracts = Vec::<Contract>::new();
+        for _ in 0..read_table_len(reader)? {
+            contracts.push(Contract::from_cpe(reader, context)?);
+        }
+
+        // Short values
+        let mut short_vals = Vec::<ShortVal>::new();
+        for _ in 0..read_table_len(reader)? {
+            short_vals.push(ShortVal::from_cpe(reader, context)?);
+        }
+
+        // Long values
+        let mut long_vals = Vec::<LongVal>::new();
+        for _ in 0..read_table_len(reader)? {
+            long_vals.push(LongVal::from_cpe(reader, context)?);
+        }
+
+        Ok(CommonTables {
+            accounts,
+            contracts,
+            short_vals,
+            long_vals,
+        })
+    }
+}
+
+fn read_table_len(reader: &mut BitReader) -> Result<usize, CPEDecodingError> {
+    let len = reader.read_uint(COMMON_TABLE_LEN_BITS)? as usize;
+
+    if len > COMMON_TABLE_SIZE {
+        return Err(CPEDecodingError::CommonTableLengthError(len as u8));
+    }
+
+    Ok(len)
+}
+
+impl CPEContext for CommonTables {
+    fn common_account(&self, common_index: u8) -> Option<Account> {
+        self.accounts.get(common_index as usize).copied()
+    }
+
+    fn common_contract(&self, common_index: u8) -> Option<Contract> {
+        self.contracts.get(common_index as usize).copied()
+    }
+
+    fn common_short_val(&self, common_index: u8) -> Option<ShortVal> {
+        self.short_vals.get(common_index as usize).copied()
+    }
+
+    fn common_long_val(&self, common_index: u8) -> Option<LongVal> {
+        self.long_vals.get(common_index as usize).copied()
+    }
+}
+
+pub struct CommonTablesContext<'a, C: CPEContext> {
+    tables: &'a CommonTables,
+    context: &'a C,
+}
+
+impl<C: CPEContext> CPEContext for CommonTablesContext<'_, C> {
+    fn account_key(&self, account_index: u32) -> Option<Key> {
+        self.context.account_key(account_index)
+    }
+
+    fn contract_id(&self, contract_index: u32) -> Option<[u8; 32]> {
+        self.context.contract_id(contract_index)
+    }
+
+    fn common_account(&self, common_index: u8) -> Option<Account> {
+        self.tables.common_account(common_index)
+    }
+
+    fn common_contract(&self, common_index: u8) -> Option<Contract> {
+        self.tables.common_contract(common_index)
+    }
+
+    fn common_short_val(&self, common_index: u8) -> Option<ShortVal> {
+        self.tables.common_short_val(common_index)
+    }
+
+    fn common_long_val(&self, common_index: u8) -> Option<LongVal> {
+        self.tables.common_long_val(common_index)
+    }
+}
+
+// Entries rewritten to reference the common tables, along with the bit savings.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompressedEntries {
+    entries: Vec<Entry>,
+    common_tables: CommonTables,
+    bits_before: usize,
+    bits_after: usize,
+    table_bits: usize,
+}
+
+impl CompressedEntries {
+    pub fn entries(&self) -> Vec<Entry> {
+        self.entries.clone()
+    }
+
+    pub fn common_tables(&self) -> CommonTables {
+        self.common_tables.clone()
+    }
+
+    // Entries bitstream length without any common references.
+    pub fn bits_before(&self) -> usize {
+        self.bits_before
+    }
+
+    // Entries bitstream length after compression.
+    pub fn bits_after(&self) -> usize {
+        self.bits_after
+    }
+
+    // Bits the values in the common tables take.
+    pub fn table_bits(&self) -> usize {
+        self.table_bits
+    }
+
+    // Net savings: the table values are paid for out of the bits the common references save.
+    pub fn bits_saved(&self) -> usize {
+        self.bits_before
+            .saturating_sub(self.bits_after + self.table_bits)
+    }
+}
+
+// Tally of the bits that making a value common would save, and what its table entry costs.
+struct Candidate<T> {
+    value: T,
+    first_seen: usize,
+    savings: usize,
+    cost: usize,
+}
+
+fn tally<T, F: Fn(&T) -> bool>(
+    candidates: &mut Vec<Candidate<T>>,
+    value: T,
+    uncommon_bits: usize,
+    is_same: F,
+) {
+    let savings = uncommon_bits.saturating_sub(COMMON_INDEX_BITS);
+
+    match candidates
+        .iter_mut()
+        .find(|candidate| is_same(&candidate.value))
+    {
+        Some(candidate) => candidate.savings += savings,
+        None => candidates.push(Candidate {
+            value,
+            first_seen: candidates.len(),
+            savings,
+            cost: uncommon_bits,
+        }),
+    }
+}
+
+// Pick up to 8 candidates with the most net savings; ties go to the earliest seen.
+// A value is only worth a table entry if its references save more than the entry costs.
+fn pick<T>(mut candidates: Vec<Candidate<T>>) -> Vec<T> {
+    candidates.retain(|candidate| candidate.savings > candidate.cost);
+    candidates.sort_by(|a, b| {
+        (b.savings - b.cost)
+            .cmp(&(a.savings - a.cost))
+            .then(a.first_seen.cmp(&b.first_seen))
+    });
+    candidates.truncate(COMMON_TABLE_SIZE);
+
+    candidates
+        .into_iter()
+        .map(|candidate| candidate.value)
+        .collect()
+}
+
+fn entries_bits(entries: &[Entry]) -> usize {
+    entries.iter().map(|entry| entry.to_cpe().len()).sum()
+}
+
+// Scan the entries, pick the 8 most beneficial recipients, contracts and values, and rewrite the
+// entries to reference them by common index. Common indexes already set on the entries are
+// re-assigned.
+pub fn compress_entries(entries: &[Entry]) -> CompressedEntries {
+    let mut accounts = Vec::<Candidate<Account>>::new();
+    let mut contracts = Vec::<Candidate<Contract>>::new();
+    let mut short_vals = Vec::<Candidate<ShortVal>>::new();
+    let mut long_vals = Vec::<Candidate<LongVal>>::new();
+
+    for entry in entries {
+        match entry {
+            Entry::Transfer(transfer) => {
+                let to = transfer.to().value();
+                tally(&mut accounts, to, to.to_cpe().len(), |account| {
+                    account.key() == to.key()
+                });
+
+                let amount = transfer.amount().value();
+                tally(&mut long_vals, amount, amount.to_cpe().len(), |long_val| {
+                    long_val.value() == amount.value()
+                });
+            }
+            Entry::Call(call) => {
+                let contract = call.contract().value();
+                tally(&mut contracts, contract, contract.to_cpe().len(), |candidate| {
+                    candidate.contract_id() == contract.contract_id()
+                });
+
+                let value = call.value().value();
+                tally(&mut short_vals, value, value.to_cpe().len(), |short_val| {
+                    short_val.value() == value.value()
+                });
+            }
+            // Lift entries are rare enough that their values are left uncommon.
+            Entry::Liftup(_) | Entry::Liftdown(_) | Entry::Recharge(_) => {}
+        }
+    }
+
+    let common_tables = CommonTables {
+        accounts: pick(accounts),
+        contracts: pick(contracts),
+        short_vals: pick(short_vals),
+        long_vals: pick(long_vals),
+    };
+
+    // Savings are measured against the entries with no common references at all.
+    let uncommon = rewrite_entries(entries, &CommonTables::default());
+    let compressed = rewrite_entries(entries, &common_tables);
+
+    // The table lengths are paid either way.
+    let table_bits = common_tables.to_cpe().len() - CommonTables::default().to_cpe().len();
+
+    CompressedEntries {
+        bits_before: entries_bits(&uncommon),
+        bits_after: entries_bits(&compressed),
+        table_bits,
+        entries: compressed,
+        common_tables,
+    }
+}
+
+fn rewrite_entries(entries: &[Entry], common_tables: &CommonTables) -> Vec<Entry> {
+    entries
+        .iter()
+        .map(|entry| match entry {
+            Entry::Transfer(transfer) => {
+                Entry::Transfer(compress_transfer(transfer, common_tables))
+            }
+            Entry::Call(call) => Entry::Call(compress_call(call, common_tables)),
+            Entry::Liftup(_) | Entry::Liftdown(_) | Entry::Recharge(_) => entry.clone(),
+        })
+        .collect()
+}
+
+fn compress_transfer(transfer: &Transfer, common_tables: &CommonTables) -> Transfer {
+    let to = transfer.to().value();
+    let to = match common_tables.account_index_of(to.key()) {
+        Some(common_index) => {
+            MaybeCommon::Common(common_tables.accounts[common_index as usize], common_index)
+        }
+        None => MaybeCommon::Uncommon(to),
+    };
+
+    let amount = transfer.amount().value();
+    let amount = match common_tables.long_val_index_of(amount.value()) {
+        Some(common_index) => MaybeCommon::Common(amount, common_index),
+        None => MaybeCommon::Uncommon(amount),
+    };
+
+    let mut compressed = Transfer::new(transfer.from(), transfer.nonce(), to, amount);
+
+    // Fees are left uncommon.
+    if let Some(fee) = transfer.fee() {
+        compressed.set_fee(fee);
+    }
+
+    compressed
+}
+
+fn compress_call(call: &Call, common_tables: &CommonTables) -> Call {
+    let value = call.value().value();
+    let value = match common_tables.short_val_index_of(value.value()) {
+        Some(common_index) => MaybeCommon::Common(value, common_index),
+        None => MaybeCommon::Uncommon(value),
+    };
+
+    let contract = call.contract().value();
+    let contract = match common_tables.contract_index_of(contract.contract_id()) {
+        Some(common_index) => {
+            MaybeCommon::Common(common_tables.contracts[common_index as usize], common_index)
+        }
+        None => MaybeCommon::Uncommon(contract),
+    };
+
+    Call::new(
+        call.caller(),
+        call.nonce(),
+        contract,
+        call.method(),
+        call.args(),
+        value,
+    )
+}
@@ -1,19 +1,134 @@
-use super::transfer::Transfer;
+use super::{
+    call::Call, liftdown::Liftdown, liftup::Liftup, recharge::Recharge, transfer::Transfer,
+};
 use crate::{
     hash::{tagged_hash, HashTag},
-    serialization::{cpe::CompactPayloadEncoding, serialize::Serialize, sighash::Sighash},
-    signature::schnorr::{schnorr_sign, Sign, SecpError, SignFlag},
+    serialization::{
+        bits::{BitReader, BitWriter},
+        cpe::{CPEContext, CPEDecodingError, CompactPayloadDecoding, CompactPayloadEncoding},
+        serialize::{ByteReader, Serialize, SerializeError},
+        sighash::Sighash,
+    },
+    signature::schnorr::{schnorr_sign, schnorr_verify, SecpError, Sign, SignFlag, Verify},
+    valtype::account::Account,
 };
-use bit_vec::BitVec;
+use std::io::{Read, Write};
+
+// Version bit of the entry type byte, set by entries that carry a nonce.
+pub const VERSION_BIT: u8 = 0x80;
 
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Entry {
     Transfer(Transfer),
+    Call(Call),
+    Liftup(Liftup),
+    Liftdown(Liftdown),
+    Recharge(Recharge),
+}
+
+impl Entry {
+    // Sequence number of the signer; each account's entries must carry consecutive nonces.
+    pub fn nonce(&self) -> u32 {
+        match self {
+            Entry::Transfer(transfer) => transfer.nonce(),
+            Entry::Call(call) => call.nonce(),
+            Entry::Liftup(liftup) => liftup.nonce(),
+            Entry::Liftdown(liftdown) => liftdown.nonce(),
+            Entry::Recharge(recharge) => recharge.nonce(),
+        }
+    }
+
+    // Account whose key signs the entry.
+    pub fn signer(&self) -> Account {
+        match self {
+            Entry::Transfer(transfer) => transfer.from(),
+            Entry::Call(call) => call.caller(),
+            Entry::Liftup(liftup) => liftup.account(),
+            Entry::Liftdown(liftdown) => liftdown.account(),
+            Entry::Recharge(recharge) => recharge.account(),
+        }
+    }
+
+}
+
+enum EntryType {
+    Transfer,
+    Call,
+    Liftup,
+    Liftdown,
+    Recharge,
+}
+
+// Peek the entry type bits, leaving them to the variant decoder.
+fn peek_entry_type(reader: &BitReader) -> Result<EntryType, CPEDecodingError> {
+    let mut peek = reader.clone();
+
+    match (peek.read_bit()?, peek.read_bit()?) {
+        (false, false) => Ok(EntryType::Transfer),
+        (false, true) => Ok(EntryType::Call),
+        // Liftup, liftdown, recharge or reserved
+        (true, second_bit) => match (second_bit, peek.read_bit()?) {
+            (false, false) => Ok(EntryType::Liftup),
+            (false, true) => Ok(EntryType::Liftdown),
+            (true, false) => Ok(EntryType::Recharge),
+            // Reserved for future upgrades
+            (true, true) => Err(CPEDecodingError::EntryTypeError),
+        },
+    }
 }
 
 impl CompactPayloadEncoding for Entry {
-    fn to_cpe(&self) -> BitVec {
+    fn write_cpe(&self, writer: &mut BitWriter) {
         match self {
-            Entry::Transfer(transfer) => transfer.to_cpe(),
+            Entry::Transfer(transfer) => transfer.write_cpe(writer),
+            Entry::Call(call) => call.write_cpe(writer),
+            Entry::Liftup(liftup) => liftup.write_cpe(writer),
+            Entry::Liftdown(liftdown) => liftdown.write_cpe(writer),
+            Entry::Recharge(recharge) => recharge.write_cpe(writer),
+        }
+    }
+}
+
+impl CompactPayloadDecoding for Entry {
+    fn from_cpe(
+        reader: &mut BitReader,
+        context: &impl CPEContext,
+    ) -> Result<Entry, CPEDecodingError> {
+        match peek_entry_type(reader)? {
+            EntryType::Transfer => Ok(Entry::Transfer(Transfer::from_cpe(reader, context)?)),
+            EntryType::Call => Ok(Entry::Call(Call::from_cpe(reader, context)?)),
+            EntryType::Liftup => Ok(Entry::Liftup(Liftup::from_cpe(reader, context)?)),
+            EntryType::Liftdown => Ok(Entry::Liftdown(Liftdown::from_cpe(reader, context)?)),
+            EntryType::Recharge => Ok(Entry::Recharge(Recharge::from_cpe(reader, context)?)),
+        }
+    }
+}
+
+// Each variant writes and checks its own entry type byte; decoding peeks it to pick the variant.
+impl Serialize for Entry {
+    fn write_to<W: Write>(&self, writer: &mut W) -> Result<(), SerializeError> {
+        match self {
+            Entry::Transfer(transfer) => transfer.write_to(writer),
+            Entry::Call(call) => call.write_to(writer),
+            Entry::Liftup(liftup) => liftup.write_to(writer),
+            Entry::Liftdown(liftdown) => liftdown.write_to(writer),
+            Entry::Recharge(recharge) => recharge.write_to(writer),
+        }
+    }
+
+    fn read_from<R: Read>(reader: &mut ByteReader<R>) -> Result<Entry, SerializeError> {
+        let offset = reader.offset();
+        let entry_type = reader.peek_u8()?;
+
+        // With or without the version bit
+        match entry_type & !VERSION_BIT {
+            0x00 => Ok(Entry::Transfer(Transfer::read_from(reader)?)),
+            0x01 => Ok(Entry::Call(Call::read_from(reader)?)),
+            0x02 => Ok(Entry::Liftup(Liftup::read_from(reader)?)),
+            0x03 => Ok(Entry::Liftdown(Liftdown::read_from(reader)?)),
+            0x04 => Ok(Entry::Recharge(Recharge::read_from(reader)?)),
+            _ => Err(SerializeError::EntryTypeError { offset, entry_type }),
         }
     }
 }
@@ -24,11 +139,15 @@ impl Sighash for Entry {
 
         sighash_preimage.extend(prev_state_hash);
 
-        let (serialized_entry, sighash_tag) = match self {
-            Entry::Transfer(transfer) => (transfer.serialize(), HashTag::SighashTransfer),
+        let sighash_tag = match self {
+            Entry::Transfer(_) => HashTag::SighashTransfer,
+            Entry::Call(_) => HashTag::SighashCall,
+            Entry::Liftup(_) => HashTag::SighashLiftup,
+            Entry::Liftdown(_) => HashTag::SighashLiftdown,
+            Entry::Recharge(_) => HashTag::SighashRecharge,
         };
 
-        sighash_preimage.extend(serialized_entry);
+        sighash_preimage.extend(self.serialize());
 
         tagged_hash(sighash_preimage, sighash_tag)
     }
@@ -43,3 +162,18 @@ impl Sign for Entry {
         schnorr_sign(secret_key, message, SignFlag::EntrySign)
     }
 }
+
+impl Verify for Entry {
+    fn verify(&self, signature: [u8; 64], prev_state_hash: [u8; 32]) -> Result<(), SecpError> {
+        // Message is the sighash of Entry.
+        let message = self.sighash(prev_state_hash);
+
+        // Verify against the signer key with the 'Entry Signing' method.
+        schnorr_verify(
+            self.signer().key().serialize(),
+            message,
+            signature,
+            SignFlag::EntrySign,
+        )
+    }
+}
@@ -0,0 +1,180 @@
+#![allow(dead_code)]
+
+use super::entry::VERSION_BIT;
+use crate::{
+    serialization::{
+        bits::{BitReader, BitWriter},
+        cpe::{CPEContext, CPEDecodingError, CompactPayloadDecoding, CompactPayloadEncoding},
+        prefix::Prefix,
+        serialize::{write_all, ByteReader, Serialize, SerializeError},
+    },
+    valtype::{
+        account::Account,
+        value::{LongVal, ShortVal},
+    },
+};
+use std::io::{Read, Write};
+
+type Bytes = Vec<u8>;
+
+// Withdraws sats from the rollup to an on-chain scriptPubKey.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Liftdown {
+    account: Account,
+    nonce: u32,
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serialization::serde_hex::bytes")
+    )]
+    spk: Bytes,
+    amount: LongVal,
+}
+
+impl Liftdown {
+    pub fn new(account: Account, nonce: u32, spk: Bytes, amount: LongVal) -> Liftdown {
+        Liftdown {
+            account,
+            nonce,
+            spk,
+            amount,
+        }
+    }
+
+    pub fn account(&self) -> Account {
+        self.account
+    }
+
+    // Sequence number of the account
+    pub fn nonce(&self) -> u32 {
+        self.nonce
+    }
+
+    // Destination scriptPubKey
+    pub fn spk(&self) -> Bytes {
+        self.spk.clone()
+    }
+
+    pub fn amount(&self) -> LongVal {
+        self.amount
+    }
+
+    pub fn set_account_index(&mut self, account_index: u32) {
+        self.account.set_account_index(account_index);
+    }
+}
+
+impl CompactPayloadEncoding for Liftdown {
+    fn write_cpe(&self, writer: &mut BitWriter) {
+        // Transfer or call
+        writer.write_bit(true);
+
+        // Liftup or liftdown
+        writer.write_bit(false);
+
+        // Liftdown
+        writer.write_bit(true);
+
+        // Account
+        self.account.write_cpe(writer);
+
+        // Nonce
+        ShortVal(self.nonce).write_cpe(writer);
+
+        // Destination scriptPubKey, length-prefixed
+        ShortVal(self.spk.len() as u32).write_cpe(writer);
+        writer.write_bytes(&self.spk);
+
+        // Amount
+        self.amount.write_cpe(writer);
+    }
+}
+
+impl CompactPayloadDecoding for Liftdown {
+    fn from_cpe(
+        reader: &mut BitReader,
+        context: &impl CPEContext,
+    ) -> Result<Liftdown, CPEDecodingError> {
+        // Transfer or call
+        if !reader.read_bit()? {
+            return Err(CPEDecodingError::EntryTypeError);
+        }
+
+        // Liftup or liftdown
+        if reader.read_bit()? {
+            return Err(CPEDecodingError::EntryTypeError);
+        }
+
+        // Liftdown
+        if !reader.read_bit()? {
+            return Err(CPEDecodingError::EntryTypeError);
+        }
+
+        // Account
+        let account = Account::from_cpe(reader, context)?;
+
+        // Nonce
+        let nonce = ShortVal::from_cpe(reader, context)?.value();
+
+        // Destination scriptPubKey
+        let spk_len = ShortVal::from_cpe(reader, context)?.value() as usize;
+
+        // Each byte takes 8 bits; reject lengths the remaining bits cannot hold.
+        if spk_len > reader.remaining() / 8 {
+            return Err(CPEDecodingError::EndOfBitsError);
+        }
+
+        let spk = reader.read_bytes(spk_len)?;
+
+        // Amount
+        let amount = LongVal::from_cpe(reader, context)?;
+
+        Ok(Liftdown::new(account, nonce, spk, amount))
+    }
+}
+
+impl Serialize for Liftdown {
+    fn write_to<W: Write>(&self, writer: &mut W) -> Result<(), SerializeError> {
+        // Entry type: 0x03 with the version bit
+        write_all(writer, &[0x03 | VERSION_BIT])?;
+
+        // Account
+        write_all(writer, &self.account.key().serialize())?;
+
+        // Nonce
+        write_all(writer, &self.nonce.to_le_bytes())?;
+
+        // Destination scriptPubKey, CompactSize-prefixed
+        write_all(writer, &self.spk.prefix_compact_size())?;
+
+        // Amount
+        write_all(writer, &self.amount.value().to_le_bytes())
+    }
+
+    fn read_from<R: Read>(reader: &mut ByteReader<R>) -> Result<Liftdown, SerializeError> {
+        // Entry type: 0x03, with the version bit unless predating nonces
+        let offset = reader.offset();
+        let entry_type = reader.read_u8()?;
+        if entry_type & !VERSION_BIT != 0x03 {
+            return Err(SerializeError::EntryTypeError { offset, entry_type });
+        }
+        let with_nonce = entry_type & VERSION_BIT != 0;
+
+        // Account
+        let account = Account::new(reader.read_key()?);
+
+        // Nonce, zero for entries predating nonces
+        let nonce = match with_nonce {
+            true => reader.read_u32_le()?,
+            false => 0,
+        };
+
+        // Destination scriptPubKey
+        let spk = reader.read_compact_size_prefixed()?;
+
+        // Amount
+        let amount = LongVal::new(reader.read_u64_le()?);
+
+        Ok(Liftdown::new(account, nonce, spk, amount))
+    }
+}
@@ -1,2 +1,7 @@
+pub mod call;
+pub mod compressor;
+pub mod entry;
+pub mod liftdown;
+pub mod liftup;
+pub mod recharge;
 pub mod transfer;
-pub mod entry;
\ No newline at end of file
@@ -0,0 +1,171 @@
+#![allow(dead_code)]
+
+use super::entry::VERSION_BIT;
+use crate::{
+    serialization::{
+        bits::{BitReader, BitWriter},
+        cpe::{CPEContext, CPEDecodingError, CompactPayloadDecoding, CompactPayloadEncoding},
+        serialize::{write_all, ByteReader, Serialize, SerializeError},
+    },
+    valtype::{
+        account::Account,
+        outpoint::Outpoint,
+        value::{LongVal, ShortVal},
+    },
+};
+use std::io::{Read, Write};
+
+// Refreshes the liquidity of a `Channel` output into a fresh `VTXO`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Recharge {
+    account: Account,
+    nonce: u32,
+    channel_outpoint: Outpoint,
+    amount: LongVal,
+}
+
+impl Recharge {
+    pub fn new(
+        account: Account,
+        nonce: u32,
+        channel_outpoint: Outpoint,
+        amount: LongVal,
+    ) -> Recharge {
+        Recharge {
+            account,
+            nonce,
+            channel_outpoint,
+            amount,
+        }
+    }
+
+    pub fn account(&self) -> Account {
+        self.account
+    }
+
+    // Sequence number of the account
+    pub fn nonce(&self) -> u32 {
+        self.nonce
+    }
+
+    pub fn channel_outpoint(&self) -> Outpoint {
+        self.channel_outpoint
+    }
+
+    // Liquidity to carry over into the fresh VTXO in sats
+    pub fn amount(&self) -> LongVal {
+        self.amount
+    }
+
+    pub fn set_account_index(&mut self, account_index: u32) {
+        self.account.set_account_index(account_index);
+    }
+}
+
+impl CompactPayloadEncoding for Recharge {
+    fn write_cpe(&self, writer: &mut BitWriter) {
+        // Transfer or call
+        writer.write_bit(true);
+
+        // Recharge or reserved
+        writer.write_bit(true);
+
+        // Recharge
+        writer.write_bit(false);
+
+        // Account
+        self.account.write_cpe(writer);
+
+        // Nonce
+        ShortVal(self.nonce).write_cpe(writer);
+
+        // Channel outpoint
+        self.channel_outpoint.write_cpe(writer);
+
+        // Amount
+        self.amount.write_cpe(writer);
+    }
+}
+
+impl CompactPayloadDecoding for Recharge {
+    fn from_cpe(
+        reader: &mut BitReader,
+        context: &impl CPEContext,
+    ) -> Result<Recharge, CPEDecodingError> {
+        // Transfer or call
+        if !reader.read_bit()? {
+            return Err(CPEDecodingError::EntryTypeError);
+        }
+
+        // Recharge or reserved
+        if !reader.read_bit()? {
+            return Err(CPEDecodingError::EntryTypeError);
+        }
+
+        // Recharge
+        if reader.read_bit()? {
+            return Err(CPEDecodingError::EntryTypeError);
+        }
+
+        // Account
+        let account = Account::from_cpe(reader, context)?;
+
+        // Nonce
+        let nonce = ShortVal::from_cpe(reader, context)?.value();
+
+        // Channel outpoint
+        let channel_outpoint = Outpoint::from_cpe(reader, context)?;
+
+        // Amount
+        let amount = LongVal::from_cpe(reader, context)?;
+
+        Ok(Recharge::new(account, nonce, channel_outpoint, amount))
+    }
+}
+
+impl Serialize for Recharge {
+    fn write_to<W: Write>(&self, writer: &mut W) -> Result<(), SerializeError> {
+        // Entry type: 0x04 with the version bit
+        write_all(writer, &[0x04 | VERSION_BIT])?;
+
+        // Account
+        write_all(writer, &self.account.key().serialize())?;
+
+        // Nonce
+        write_all(writer, &self.nonce.to_le_bytes())?;
+
+        // Channel outpoint
+        self.channel_outpoint.write_to(writer)?;
+
+        // Amount
+        write_all(writer, &self.amount.value().to_le_bytes())
+    }
+
+    fn read_from<R: Read>(reader: &mut ByteReader<R>) -> Result<Recharge, SerializeError> {
+        // Entry type: 0x04, with the version bit unless predating nonces
+        let offset = reader.offset();
+        let entry_type = reader.read_u8()?;
+        if entry_type & !VERSION_BIT != 0x04 {
+            return Err(SerializeError::EntryTypeError { offset, entry_type });
+        }
+        let with_nonce = entry_type & VERSION_BIT != 0;
+
+        // Account
+        let account = Account::new(reader.read_key()?);
+
+        // Nonce, zero for entries predating nonces
+        let nonce = match with_nonce {
+            true => reader.read_u32_le()?,
+            false => 0,
+        };
+
+        // Channel outpoint
+        let channel_outpoint = Outpoint::read_from(reader)?;
+
+        // Amount
+        let amount = LongVal::new(reader.read_u64_le()?);
+
+        Ok(Recharge::new(account, nonce, channel_outpoint, amount))
+    }
+}
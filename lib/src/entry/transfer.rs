@@ -1,49 +1,94 @@
 #![allow(dead_code)]
 
-use bit_vec::BitVec;
-use musig2::secp256k1::XOnlyPublicKey;
-
-type Bytes = Vec<u8>;
-type Key = XOnlyPublicKey;
+use std::io::{Read, Write};
 
 use crate::{
     serialization::{
-        cpe::CompactPayloadEncoding,
-        serialize::{Serialize, SerializeError},
+        bits::{BitReader, BitWriter},
+        cpe::{CPEContext, CPEDecodingError, CompactPayloadDecoding, CompactPayloadEncoding},
+        serialize::{write_all, ByteReader, Serialize, SerializeError},
+    },
+    valtype::{
+        account::Account,
+        maybe_common::MaybeCommon,
+        value::{LongVal, ShortVal},
     },
-    valtype::{account::Account, maybe_common::MaybeCommon, value::ShortVal},
 };
 
-#[derive(Clone, Copy)]
+use super::entry::VERSION_BIT;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Transfer {
     from: Account,
+    nonce: u32,
     to: MaybeCommon<Account>,
-    amount: MaybeCommon<ShortVal>,
+    amount: MaybeCommon<LongVal>,
+    fee: Option<LongVal>,
 }
 
 impl Transfer {
-    pub fn new(from: Account, to: MaybeCommon<Account>, amount: MaybeCommon<ShortVal>) -> Transfer {
-        Transfer { from, to, amount }
+    pub fn new(
+        from: Account,
+        nonce: u32,
+        to: MaybeCommon<Account>,
+        amount: MaybeCommon<LongVal>,
+    ) -> Transfer {
+        Transfer {
+            from,
+            nonce,
+            to,
+            amount,
+            fee: None,
+        }
     }
 
-    pub fn new_uncommon(from: Account, to: Account, amount: ShortVal) -> Transfer {
+    pub fn new_uncommon(from: Account, nonce: u32, to: Account, amount: LongVal) -> Transfer {
         Transfer {
             from,
+            nonce,
             to: MaybeCommon::Uncommon(to),
             amount: MaybeCommon::Uncommon(amount),
+            fee: None,
         }
     }
 
+    pub fn from(&self) -> Account {
+        self.from
+    }
+
+    // Sequence number of the sender
+    pub fn nonce(&self) -> u32 {
+        self.nonce
+    }
+
+    pub fn to(&self) -> MaybeCommon<Account> {
+        self.to
+    }
+
+    pub fn amount(&self) -> MaybeCommon<LongVal> {
+        self.amount
+    }
+
+    // Operator fee paid by the sender on top of the amount
+    pub fn fee(&self) -> Option<LongVal> {
+        self.fee
+    }
+
+    pub fn set_fee(&mut self, fee: LongVal) {
+        self.fee = Some(fee);
+    }
+
     pub fn set_from_account_index(&mut self, account_index: u32) {
         self.from.set_account_index(account_index);
     }
 
     pub fn set_to_account_index(&mut self, account_index: u32) {
-        match self.to {
-            MaybeCommon::Uncommon(mut to) => {
+        match &mut self.to {
+            MaybeCommon::Uncommon(to) => {
                 to.set_account_index(account_index);
             }
-            MaybeCommon::Common(mut to, _) => {
+            MaybeCommon::Common(to, _) => {
                 to.set_account_index(account_index);
             }
         }
@@ -69,82 +114,148 @@ impl Transfer {
 }
 
 impl CompactPayloadEncoding for Transfer {
-    fn to_cpe(&self) -> BitVec {
-        let mut bit_vec = BitVec::new();
+    fn write_cpe(&self, writer: &mut BitWriter) {
+        // Transfer or call
+        writer.write_bit(false);
+
+        // Transfer
+        writer.write_bit(false);
+
+        // From
+        self.from.write_cpe(writer);
+
+        // Nonce
+        ShortVal(self.nonce).write_cpe(writer);
+
+        // To
+        self.to.write_cpe(writer);
 
+        // Amount
+        self.amount.write_cpe(writer);
+
+        // Fee
+        match self.fee {
+            None => writer.write_bit(false),
+            Some(fee) => {
+                writer.write_bit(true);
+                fee.write_cpe(writer);
+            }
+        }
+    }
+}
+
+impl CompactPayloadDecoding for Transfer {
+    fn from_cpe(
+        reader: &mut BitReader,
+        context: &impl CPEContext,
+    ) -> Result<Transfer, CPEDecodingError> {
         // Transfer or call
-        bit_vec.push(false);
+        if reader.read_bit()? {
+            return Err(CPEDecodingError::EntryTypeError);
+        }
 
         // Transfer
-        bit_vec.push(false);
+        if reader.read_bit()? {
+            return Err(CPEDecodingError::EntryTypeError);
+        }
 
         // From
-        bit_vec.extend(self.from.to_cpe());
+        let from = Account::from_cpe(reader, context)?;
+
+        // Nonce
+        let nonce = ShortVal::from_cpe(reader, context)?.value();
 
         // To
-        bit_vec.extend(self.to.to_cpe());
+        let to = MaybeCommon::<Account>::from_cpe(reader, context)?;
 
         // Amount
-        bit_vec.extend(self.amount.to_cpe());
+        let amount = MaybeCommon::<LongVal>::from_cpe(reader, context)?;
 
-        bit_vec
+        let mut transfer = Transfer::new(from, nonce, to, amount);
+
+        // Fee
+        if reader.read_bit()? {
+            transfer.set_fee(LongVal::from_cpe(reader, context)?);
+        }
+
+        Ok(transfer)
     }
 }
 
 impl Serialize for Transfer {
-    fn serialize(&self) -> Bytes {
-        let mut bytes = Vec::<u8>::new();
-
-        // Entry type: 0x00
-        bytes.push(0x00);
+    fn write_to<W: Write>(&self, writer: &mut W) -> Result<(), SerializeError> {
+        // Entry type: 0x00 with the version bit
+        write_all(writer, &[VERSION_BIT])?;
 
         // From
-        let from = self.from.key().serialize();
-        bytes.extend(from);
+        write_all(writer, &self.from.key().serialize())?;
+
+        // Nonce
+        write_all(writer, &self.nonce.to_le_bytes())?;
 
         // To
-        let to = match self.to {
-            MaybeCommon::Uncommon(to) => to.key().serialize(),
-            MaybeCommon::Common(to, _) => to.key().serialize(),
-        };
-        bytes.extend(to);
+        write_all(writer, &self.to.value().key().serialize())?;
 
         // Amount
-        let amount = match self.amount {
-            MaybeCommon::Uncommon(amount) => amount.value(),
-            MaybeCommon::Common(amount, _) => amount.value(),
-        };
-        bytes.extend(amount.to_le_bytes());
+        write_all(writer, &self.amount.value().value().to_le_bytes())?;
 
-        bytes
+        // Fee: presence flag, then the fee
+        match self.fee {
+            None => write_all(writer, &[0x00]),
+            Some(fee) => {
+                write_all(writer, &[0x01])?;
+                write_all(writer, &fee.value().to_le_bytes())
+            }
+        }
     }
 
-    fn from_bytes(bytes: Bytes) -> Result<Transfer, SerializeError> {
-        // Entry type: 0x00
-        let entry_type = &bytes[0..1];
-        if entry_type != &[0x00] {
-            return Err(SerializeError::EntryTypeError);
+    fn read_from<R: Read>(reader: &mut ByteReader<R>) -> Result<Transfer, SerializeError> {
+        // Entry type: 0x00, with the version bit unless predating nonces
+        let offset = reader.offset();
+        let entry_type = reader.read_u8()?;
+        if entry_type & !VERSION_BIT != 0x00 {
+            return Err(SerializeError::EntryTypeError { offset, entry_type });
         }
+        let is_legacy = entry_type & VERSION_BIT == 0;
 
         // From
-        let from = &bytes[1..33];
-        let from_key = Key::from_slice(from).map_err(|_| SerializeError::KeyParseError)?;
-        let from_account = Account::new(from_key);
+        let from_account = Account::new(reader.read_key()?);
+
+        // Nonce, zero for transfers predating nonces
+        let nonce = match is_legacy {
+            true => 0,
+            false => reader.read_u32_le()?,
+        };
 
         // To
-        let to = &bytes[33..65];
-        let to_key = Key::from_slice(to).map_err(|_| SerializeError::KeyParseError)?;
-        let to_account = Account::new(to_key);
+        let to_account = Account::new(reader.read_key()?);
 
         // Amount
-        let amount: &[u8] = &bytes[65..69];
-        let amount_u32 = u32::from_le_bytes([amount[0], amount[1], amount[2], amount[3]]);
-        let amount_short_val = ShortVal::new(amount_u32);
-
-        Ok(Transfer::new_uncommon(
-            from_account,
-            to_account,
-            amount_short_val,
-        ))
+        let amount = match is_legacy {
+            true => reader.read_u32_le()? as u64,
+            false => reader.read_u64_le()?,
+        };
+
+        let mut transfer =
+            Transfer::new_uncommon(from_account, nonce, to_account, LongVal::new(amount));
+
+        if is_legacy {
+            return Ok(transfer);
+        }
+
+        // Fee
+        let flag_offset = reader.offset();
+        match reader.read_u8()? {
+            0x00 => (),
+            0x01 => transfer.set_fee(LongVal::new(reader.read_u64_le()?)),
+            flag => {
+                return Err(SerializeError::InvalidFlag {
+                    offset: flag_offset,
+                    flag,
+                })
+            }
+        }
+
+        Ok(transfer)
     }
 }
@@ -39,6 +39,7 @@ pub enum HashTag {
     TapLeaf,
     TapBranch,
     TapTweak,
+    TapSighash,
     SighashTransfer,
     SighashCall,
     SighashLiftup,
@@ -50,6 +51,9 @@ pub enum HashTag {
     EntryChallenge,
     ProtocolMessageChallenge,
     CustomMessageChallenge,
+    ContractId,
+    GenesisState,
+    StateHash,
     CustomTag(String),
 }
 
@@ -60,6 +64,7 @@ pub fn tagged_hash(data: impl AsRef<[u8]>, tag: HashTag) -> [u8; 32] {
         HashTag::TapLeaf => Sha256::digest("TapLeaf"),
         HashTag::TapBranch => Sha256::digest("TapBranch"),
         HashTag::TapTweak => Sha256::digest("TapTweak"),
+        HashTag::TapSighash => Sha256::digest("TapSighash"),
         HashTag::SighashTransfer => Sha256::digest("Brollup/sighashtransfer"),
         HashTag::SighashCall => Sha256::digest("Brollup/sighashcall"),
         HashTag::SighashLiftup => Sha256::digest("Brollup/sighashliftup"),
@@ -71,6 +76,9 @@ pub fn tagged_hash(data: impl AsRef<[u8]>, tag: HashTag) -> [u8; 32] {
         HashTag::EntryChallenge => Sha256::digest("Brollup/entry/challenge"),
         HashTag::ProtocolMessageChallenge => Sha256::digest("Brollup/protocolmessage/challenge"),
         HashTag::CustomMessageChallenge => Sha256::digest("Brollup/custommessage/challenge"),
+        HashTag::ContractId => Sha256::digest("Brollup/contractid"),
+        HashTag::GenesisState => Sha256::digest("Brollup/genesisstate"),
+        HashTag::StateHash => Sha256::digest("Brollup/statehash"),
         HashTag::CustomTag(tag) => Sha256::digest(tag),
     };
 
@@ -0,0 +1,229 @@
+#![allow(dead_code)]
+
+use crate::{
+    entry::{entry::Entry, liftdown::Liftdown, liftup::Liftup, transfer::Transfer},
+    hash::{tagged_hash, HashTag},
+    serialization::sighash::Sighash,
+    signature::schnorr::Verify,
+    txo::payload::DecodedPayload,
+};
+use musig2::secp256k1::XOnlyPublicKey;
+use std::collections::HashMap;
+
+type Key = XOnlyPublicKey;
+
+#[derive(Debug, PartialEq)]
+pub enum LedgerError {
+    UnknownSender(Key),
+    InvalidNonce { expected: u32, nonce: u32 },
+    InsufficientBalance { balance: u64, amount: u64 },
+    BalanceOverflow,
+    InvalidSignature,
+    NonceExhausted,
+    UnsupportedEntry,
+}
+
+// Account balances, next expected nonces, collected fees and the state hash chained over every applied entry.
+// State hash: H_StateHash(prev_state_hash || sighash(entry, prev_state_hash))
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ledger {
+    balances: HashMap<Key, u64>,
+    nonces: HashMap<Key, u32>,
+    fees: u64,
+    state_hash: [u8; 32],
+}
+
+impl Ledger {
+    // Start from the genesis allocations; the genesis state hash commits to them in the given order.
+    // H_GenesisState(key (32) || balance (8 LE) || ...)
+    pub fn genesis(allocations: &[(Key, u64)]) -> Result<Ledger, LedgerError> {
+        let mut balances = HashMap::<Key, u64>::new();
+        let mut preimage = Vec::<u8>::new();
+
+        for (key, amount) in allocations {
+            let balance = balances.entry(*key).or_insert(0);
+            *balance = balance
+                .checked_add(*amount)
+                .ok_or(LedgerError::BalanceOverflow)?;
+
+            preimage.extend(key.serialize());
+            preimage.extend(amount.to_le_bytes());
+        }
+
+        Ok(Ledger {
+            balances,
+            nonces: HashMap::new(),
+            fees: 0,
+            state_hash: tagged_hash(preimage, HashTag::GenesisState),
+        })
+    }
+
+    // Rebuild the ledger by replaying confirmed payloads in order from genesis.
+    pub fn replay(
+        allocations: &[(Key, u64)],
+        payloads: &[DecodedPayload],
+    ) -> Result<Ledger, LedgerError> {
+        let mut ledger = Ledger::genesis(allocations)?;
+
+        for payload in payloads {
+            ledger.apply_payload(payload)?;
+        }
+
+        Ok(ledger)
+    }
+
+    pub fn state_hash(&self) -> [u8; 32] {
+        self.state_hash
+    }
+
+    // Balance of the key, or none if the key never held funds.
+    pub fn balance(&self, key: &Key) -> Option<u64> {
+        self.balances.get(key).copied()
+    }
+
+    // Operator fees collected from applied transfers.
+    pub fn fees(&self) -> u64 {
+        self.fees
+    }
+
+    // Nonce the next entry signed by the key must carry.
+    pub fn next_nonce(&self, key: &Key) -> u32 {
+        self.nonces.get(key).copied().unwrap_or(0)
+    }
+
+    // Verify the entry signature against the current state and apply it.
+    // Returns the new state hash.
+    pub fn apply(&mut self, entry: &Entry, signature: [u8; 64]) -> Result<[u8; 32], LedgerError> {
+        entry
+            .verify(signature, self.state_hash)
+            .map_err(|_| LedgerError::InvalidSignature)?;
+
+        self.execute(entry)
+    }
+
+    // Apply the entries of a confirmed payload, all or none of them.
+    // Signatures were checked before inclusion and are not carried in the payload.
+    pub fn apply_payload(&mut self, payload: &DecodedPayload) -> Result<[u8; 32], LedgerError> {
+        let mut ledger = self.clone();
+
+        for entry in payload.entries() {
+            ledger.execute(&entry)?;
+        }
+
+        *self = ledger;
+
+        Ok(self.state_hash)
+    }
+
+    fn execute(&mut self, entry: &Entry) -> Result<[u8; 32], LedgerError> {
+        // An entry is valid exactly once: its nonce must match the signer's next nonce.
+        let signer = entry.signer().key();
+        let expected = self.next_nonce(&signer);
+        if entry.nonce() != expected {
+            return Err(LedgerError::InvalidNonce {
+                expected,
+                nonce: entry.nonce(),
+            });
+        }
+
+        // Nonces are u32; an exhausted account can no longer sign entries.
+        let next_nonce = expected.checked_add(1).ok_or(LedgerError::NonceExhausted)?;
+
+        match entry {
+            Entry::Transfer(transfer) => self.execute_transfer(transfer)?,
+            Entry::Liftup(liftup) => self.execute_liftup(liftup)?,
+            Entry::Liftdown(liftdown) => self.execute_liftdown(liftdown)?,
+            Entry::Call(_) | Entry::Recharge(_) => return Err(LedgerError::UnsupportedEntry),
+        }
+
+        self.nonces.insert(signer, next_nonce);
+
+        let mut preimage = Vec::<u8>::new();
+        preimage.extend(self.state_hash);
+        preimage.extend(entry.sighash(self.state_hash));
+
+        self.state_hash = tagged_hash(preimage, HashTag::StateHash);
+
+        Ok(self.state_hash)
+    }
+
+    // Balances are checked before any of them is modified, so a failed transfer leaves no trace.
+    fn execute_transfer(&mut self, transfer: &Transfer) -> Result<(), LedgerError> {
+        let from = transfer.from().key();
+        let to = transfer.to().value().key();
+        let amount = transfer.amount().value().value();
+        let fee = transfer.fee().map(|fee| fee.value()).unwrap_or(0);
+
+        let from_balance = self
+            .balance(&from)
+            .ok_or(LedgerError::UnknownSender(from))?;
+
+        // The sender pays the fee on top of the amount.
+        let total = amount
+            .checked_add(fee)
+            .ok_or(LedgerError::BalanceOverflow)?;
+
+        if from_balance < total {
+            return Err(LedgerError::InsufficientBalance {
+                balance: from_balance,
+                amount: total,
+            });
+        }
+
+        let fees = self
+            .fees
+            .checked_add(fee)
+            .ok_or(LedgerError::BalanceOverflow)?;
+
+        // Self-transfers only pay the fee.
+        if from == to {
+            self.balances.insert(from, from_balance - fee);
+            self.fees = fees;
+            return Ok(());
+        }
+
+        let to_balance = self
+            .balance(&to)
+            .unwrap_or(0)
+            .checked_add(amount)
+            .ok_or(LedgerError::BalanceOverflow)?;
+
+        self.balances.insert(from, from_balance - total);
+        self.balances.insert(to, to_balance);
+        self.fees = fees;
+
+        Ok(())
+    }
+    // The lifted sats are credited to the account.
+    fn execute_liftup(&mut self, liftup: &Liftup) -> Result<(), LedgerError> {
+        let account = liftup.account().key();
+
+        let balance = self
+            .balance(&account)
+            .unwrap_or(0)
+            .checked_add(liftup.amount().value())
+            .ok_or(LedgerError::BalanceOverflow)?;
+
+        self.balances.insert(account, balance);
+
+        Ok(())
+    }
+
+    // The withdrawn sats are debited from the account.
+    fn execute_liftdown(&mut self, liftdown: &Liftdown) -> Result<(), LedgerError> {
+        let account = liftdown.account().key();
+        let amount = liftdown.amount().value();
+
+        let balance = self
+            .balance(&account)
+            .ok_or(LedgerError::UnknownSender(account))?;
+
+        if balance < amount {
+            return Err(LedgerError::InsufficientBalance { balance, amount });
+        }
+
+        self.balances.insert(account, balance - amount);
+
+        Ok(())
+    }
+}
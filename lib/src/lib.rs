@@ -1,3 +1,4 @@
+pub mod script;
 pub mod serialization;
 pub mod taproot;
 pub mod txo;
@@ -5,4 +6,9 @@ pub mod well_known;
 pub mod hash;
 pub mod signature;
 pub mod entry;
-pub mod valtype;
\ No newline at end of file
+pub mod valtype;
+pub mod registry;
+pub mod ledger;
+pub mod mempool;
+pub mod tx;
+pub mod covenant;
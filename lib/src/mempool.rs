@@ -0,0 +1,137 @@
+#![allow(dead_code)]
+
+use crate::{
+    entry::entry::Entry,
+    ledger::{Ledger, LedgerError},
+    serialization::cpe::CompactPayloadEncoding,
+    txo::payload::Payload,
+};
+
+#[derive(Debug, PartialEq)]
+pub enum MempoolError {
+    DuplicateEntry,
+    ConflictingEntry { nonce: u32 },
+    LedgerError(LedgerError),
+    BatchTooLarge,
+}
+
+// Signed entries collected between rounds, in the order they are applied.
+// Every entry is signed against the state left by the entries before it, so batches are taken
+// from the front and the pending ledger always reflects the whole pool.
+#[derive(Clone, Debug)]
+pub struct Mempool {
+    entries: Vec<(Entry, [u8; 64])>,
+    pending: Ledger,
+}
+
+impl Mempool {
+    pub fn new(ledger: &Ledger) -> Mempool {
+        Mempool {
+            entries: Vec::new(),
+            pending: ledger.clone(),
+        }
+    }
+
+    pub fn entries(&self) -> Vec<Entry> {
+        self.entries
+            .iter()
+            .map(|(entry, _)| entry.clone())
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // Ledger state with every pooled entry applied.
+    pub fn pending(&self) -> &Ledger {
+        &self.pending
+    }
+
+    // State hash the next entry must be signed against.
+    pub fn state_hash(&self) -> [u8; 32] {
+        self.pending.state_hash()
+    }
+
+    // Validate the entry against the pending state and append it to the pool.
+    // Returns the new pending state hash.
+    pub fn insert(&mut self, entry: Entry, signature: [u8; 64]) -> Result<[u8; 32], MempoolError> {
+        let signer = entry.signer().key();
+
+        for (pooled, pooled_signature) in self.entries.iter() {
+            if pooled.signer().key() != signer || pooled.nonce() != entry.nonce() {
+                continue;
+            }
+
+            // First seen wins; a different entry reusing the nonce is rejected.
+            return match *pooled == entry && *pooled_signature == signature {
+                true => Err(MempoolError::DuplicateEntry),
+                false => Err(MempoolError::ConflictingEntry {
+                    nonce: entry.nonce(),
+                }),
+            };
+        }
+
+        let state_hash = self
+            .pending
+            .apply(&entry, signature)
+            .map_err(MempoolError::LedgerError)?;
+
+        self.entries.push((entry, signature));
+
+        Ok(state_hash)
+    }
+
+    // Take the longest run of entries from the front whose payload data pushes fit in the target size.
+    pub fn batch(&self, target_len: usize) -> Result<Vec<Entry>, MempoolError> {
+        if Payload::data_push_len(0) > target_len {
+            return Err(MempoolError::BatchTooLarge);
+        }
+
+        let mut entries_bits: usize = 0;
+        let mut batch = Vec::<Entry>::new();
+
+        for (entry, _) in self.entries.iter() {
+            let bits = entries_bits + entry.to_cpe().len();
+
+            if Payload::data_push_len(bits) > target_len {
+                break;
+            }
+
+            entries_bits = bits;
+            batch.push(entry.clone());
+        }
+
+        Ok(batch)
+    }
+
+    // Rebase the pool onto a newly confirmed ledger state.
+    // Entries whose nonce was consumed are dropped: either they were confirmed or they conflict
+    // with an entry that was. The rest are re-applied in order and evicted if no longer valid.
+    // Returns the evicted entries.
+    pub fn sync(&mut self, ledger: &Ledger) -> Vec<Entry> {
+        let mut pending = ledger.clone();
+        let mut entries = Vec::<(Entry, [u8; 64])>::new();
+        let mut evicted = Vec::<Entry>::new();
+
+        for (entry, signature) in self.entries.drain(..) {
+            if entry.nonce() < ledger.next_nonce(&entry.signer().key()) {
+                continue;
+            }
+
+            match pending.apply(&entry, signature) {
+                Ok(_) => entries.push((entry, signature)),
+                Err(_) => evicted.push(entry),
+            }
+        }
+
+        self.entries = entries;
+        self.pending = pending;
+
+        evicted
+    }
+}
@@ -0,0 +1,212 @@
+#![allow(dead_code)]
+
+use crate::{
+    entry::entry::Entry,
+    serialization::{
+        cpe::CPEContext,
+        serialize::{write_all, ByteReader, Serialize, SerializeError},
+    },
+    txo::payload::{DecodedPayload, Payload, PayloadError},
+    valtype::account::Account,
+};
+use musig2::secp256k1::XOnlyPublicKey;
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+};
+
+type Key = XOnlyPublicKey;
+
+// Assigns sequential account indexes to keys in the order they first appear in payloads.
+// Once assigned, an index never changes; accounts can then be encoded compactly by their index.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AccountRegistry {
+    keys: Vec<Key>,
+    indexes: HashMap<Key, u32>,
+}
+
+impl AccountRegistry {
+    pub fn new() -> AccountRegistry {
+        AccountRegistry {
+            keys: Vec::new(),
+            indexes: HashMap::new(),
+        }
+    }
+
+    // Rebuild the registry by replaying payload tapscripts in the order they were confirmed.
+    // Each payload is decoded against the registry built from the payloads preceding it.
+    pub fn from_tapscripts<T: AsRef<[u8]>>(
+        tap_scripts: &[T],
+    ) -> Result<AccountRegistry, PayloadError> {
+        let mut registry = AccountRegistry::new();
+
+        for tap_script in tap_scripts {
+            let payload = Payload::from_tapscript(tap_script.as_ref(), &registry)?;
+            registry.register_payload(&payload);
+        }
+
+        Ok(registry)
+    }
+
+    // Return the index of the key, assigning the next index if the key is new.
+    pub fn register(&mut self, key: Key) -> u32 {
+        if let Some(index) = self.indexes.get(&key) {
+            return *index;
+        }
+
+        let index =
+            u32::try_from(self.keys.len()).expect("Account registry cannot exceed u32 indexes.");
+
+        self.keys.push(key);
+        self.indexes.insert(key, index);
+
+        index
+    }
+
+    // Register senders and recipients in order of appearance.
+    pub fn register_entries(&mut self, entries: &[Entry]) {
+        for entry in entries {
+            match entry {
+                Entry::Transfer(transfer) => {
+                    self.register(transfer.from().key());
+                    self.register(transfer.to().value().key());
+                }
+                Entry::Call(call) => {
+                    self.register(call.caller().key());
+                }
+                Entry::Liftup(liftup) => {
+                    self.register(liftup.account().key());
+                }
+                Entry::Liftdown(liftdown) => {
+                    self.register(liftdown.account().key());
+                }
+                Entry::Recharge(recharge) => {
+                    self.register(recharge.account().key());
+                }
+            }
+        }
+    }
+
+    pub fn register_payload(&mut self, payload: &DecodedPayload) {
+        self.register_entries(&payload.entries());
+    }
+
+    pub fn index_of(&self, key: &Key) -> Option<u32> {
+        self.indexes.get(key).copied()
+    }
+
+    pub fn key_at(&self, index: u32) -> Option<Key> {
+        self.keys.get(index as usize).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    // Compact form of the account if its key is registered.
+    pub fn compact(&self, account: Account) -> Account {
+        match self.index_of(&account.key()) {
+            Some(index) => Account::new_compact(account.key(), index),
+            None => Account::new(account.key()),
+        }
+    }
+
+    // Set account indexes on entries for all registered keys.
+    pub fn compact_entries(&self, entries: &[Entry]) -> Vec<Entry> {
+        entries
+            .iter()
+            .map(|entry| match entry {
+                Entry::Transfer(transfer) => {
+                    let mut transfer = *transfer;
+
+                    if let Some(index) = self.index_of(&transfer.from().key()) {
+                        transfer.set_from_account_index(index);
+                    }
+
+                    if let Some(index) = self.index_of(&transfer.to().value().key()) {
+                        transfer.set_to_account_index(index);
+                    }
+
+                    Entry::Transfer(transfer)
+                }
+                Entry::Call(call) => {
+                    let mut call = call.clone();
+
+                    if let Some(index) = self.index_of(&call.caller().key()) {
+                        call.set_caller_account_index(index);
+                    }
+
+                    Entry::Call(call)
+                }
+                Entry::Liftup(liftup) => {
+                    let mut liftup = *liftup;
+
+                    if let Some(index) = self.index_of(&liftup.account().key()) {
+                        liftup.set_account_index(index);
+                    }
+
+                    Entry::Liftup(liftup)
+                }
+                Entry::Liftdown(liftdown) => {
+                    let mut liftdown = liftdown.clone();
+
+                    if let Some(index) = self.index_of(&liftdown.account().key()) {
+                        liftdown.set_account_index(index);
+                    }
+
+                    Entry::Liftdown(liftdown)
+                }
+                Entry::Recharge(recharge) => {
+                    let mut recharge = *recharge;
+
+                    if let Some(index) = self.index_of(&recharge.account().key()) {
+                        recharge.set_account_index(index);
+                    }
+
+                    Entry::Recharge(recharge)
+                }
+            })
+            .collect()
+    }
+}
+
+impl CPEContext for AccountRegistry {
+    fn account_key(&self, account_index: u32) -> Option<Key> {
+        self.key_at(account_index)
+    }
+}
+
+// Persisted as the number of keys (u32 LE) followed by the keys in index order.
+impl Serialize for AccountRegistry {
+    fn write_to<W: Write>(&self, writer: &mut W) -> Result<(), SerializeError> {
+        write_all(writer, &(self.keys.len() as u32).to_le_bytes())?;
+
+        for key in &self.keys {
+            write_all(writer, &key.serialize())?;
+        }
+
+        Ok(())
+    }
+
+    fn read_from<R: Read>(reader: &mut ByteReader<R>) -> Result<AccountRegistry, SerializeError> {
+        let num_keys = reader.read_u32_le()?;
+        let mut registry = AccountRegistry::new();
+
+        for _ in 0..num_keys {
+            let offset = reader.offset();
+            let key = reader.read_key()?;
+
+            if registry.index_of(&key).is_some() {
+                return Err(SerializeError::DuplicateKey { offset });
+            }
+
+            registry.register(key);
+        }
+
+        Ok(registry)
+    }
+}
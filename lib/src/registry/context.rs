@@ -0,0 +1,52 @@
+#![allow(dead_code)]
+
+use super::{account::AccountRegistry, contract::ContractRegistry};
+use crate::serialization::cpe::CPEContext;
+use musig2::secp256k1::XOnlyPublicKey;
+
+type Key = XOnlyPublicKey;
+
+// Account and contract registries together, resolving both kinds of compact indexes.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RegistryContext {
+    account_registry: AccountRegistry,
+    contract_registry: ContractRegistry,
+}
+
+impl RegistryContext {
+    pub fn new(
+        account_registry: AccountRegistry,
+        contract_registry: ContractRegistry,
+    ) -> RegistryContext {
+        RegistryContext {
+            account_registry,
+            contract_registry,
+        }
+    }
+
+    pub fn account_registry(&self) -> &AccountRegistry {
+        &self.account_registry
+    }
+
+    pub fn account_registry_mut(&mut self) -> &mut AccountRegistry {
+        &mut self.account_registry
+    }
+
+    pub fn contract_registry(&self) -> &ContractRegistry {
+        &self.contract_registry
+    }
+
+    pub fn contract_registry_mut(&mut self) -> &mut ContractRegistry {
+        &mut self.contract_registry
+    }
+}
+
+impl CPEContext for RegistryContext {
+    fn account_key(&self, account_index: u32) -> Option<Key> {
+        self.account_registry.key_at(account_index)
+    }
+
+    fn contract_id(&self, contract_index: u32) -> Option<[u8; 32]> {
+        self.contract_registry.contract_id_at(contract_index)
+    }
+}
@@ -0,0 +1,135 @@
+#![allow(dead_code)]
+
+use crate::{
+    hash::{tagged_hash, HashTag},
+    serialization::{
+        cpe::CPEContext,
+        prefix::Prefix,
+        serialize::{write_all, ByteReader, Serialize, SerializeError},
+    },
+    valtype::contract::Contract,
+};
+use musig2::secp256k1::XOnlyPublicKey;
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+};
+
+type Key = XOnlyPublicKey;
+
+// Contract ID: tagged hash of the deployer key, the deployer's deployment nonce and the program.
+// H_ContractId(deployer (32) || nonce (4 LE) || compact_size(program) || program)
+pub fn derive_contract_id(deployer: Key, nonce: u32, program: &[u8]) -> [u8; 32] {
+    let mut preimage = Vec::<u8>::new();
+
+    preimage.extend(deployer.serialize());
+    preimage.extend(nonce.to_le_bytes());
+    preimage.extend(program.to_vec().prefix_compact_size());
+
+    tagged_hash(preimage, HashTag::ContractId)
+}
+
+// Assigns sequential contract indexes to contract IDs in deployment order.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ContractRegistry {
+    contract_ids: Vec<[u8; 32]>,
+    indexes: HashMap<[u8; 32], u32>,
+}
+
+impl ContractRegistry {
+    pub fn new() -> ContractRegistry {
+        ContractRegistry {
+            contract_ids: Vec::new(),
+            indexes: HashMap::new(),
+        }
+    }
+
+    // Deploy a program and return the compact contract, or none if the contract ID is taken.
+    pub fn deploy(&mut self, deployer: Key, nonce: u32, program: &[u8]) -> Option<Contract> {
+        let contract_id = derive_contract_id(deployer, nonce, program);
+
+        if self.indexes.contains_key(&contract_id) {
+            return None;
+        }
+
+        let index = self.register(contract_id);
+
+        Some(Contract::new_compact(contract_id, index))
+    }
+
+    // Return the index of the contract ID, assigning the next index if the contract ID is new.
+    pub fn register(&mut self, contract_id: [u8; 32]) -> u32 {
+        if let Some(index) = self.indexes.get(&contract_id) {
+            return *index;
+        }
+
+        let index = u32::try_from(self.contract_ids.len())
+            .expect("Contract registry cannot exceed u32 indexes.");
+
+        self.contract_ids.push(contract_id);
+        self.indexes.insert(contract_id, index);
+
+        index
+    }
+
+    pub fn index_of(&self, contract_id: &[u8; 32]) -> Option<u32> {
+        self.indexes.get(contract_id).copied()
+    }
+
+    pub fn contract_id_at(&self, index: u32) -> Option<[u8; 32]> {
+        self.contract_ids.get(index as usize).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.contract_ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.contract_ids.is_empty()
+    }
+
+    // Compact form of the contract if its ID is registered.
+    pub fn compact(&self, contract: Contract) -> Contract {
+        match self.index_of(&contract.contract_id()) {
+            Some(index) => Contract::new_compact(contract.contract_id(), index),
+            None => Contract::new(contract.contract_id()),
+        }
+    }
+}
+
+impl CPEContext for ContractRegistry {
+    fn contract_id(&self, contract_index: u32) -> Option<[u8; 32]> {
+        self.contract_id_at(contract_index)
+    }
+}
+
+// Persisted as the number of contract IDs (u32 LE) followed by the IDs in index order.
+impl Serialize for ContractRegistry {
+    fn write_to<W: Write>(&self, writer: &mut W) -> Result<(), SerializeError> {
+        write_all(writer, &(self.contract_ids.len() as u32).to_le_bytes())?;
+
+        for contract_id in &self.contract_ids {
+            write_all(writer, contract_id)?;
+        }
+
+        Ok(())
+    }
+
+    fn read_from<R: Read>(reader: &mut ByteReader<R>) -> Result<ContractRegistry, SerializeError> {
+        let num_contract_ids = reader.read_u32_le()?;
+        let mut registry = ContractRegistry::new();
+
+        for _ in 0..num_contract_ids {
+            let offset = reader.offset();
+            let contract_id = reader.read_array::<32>()?;
+
+            if registry.index_of(&contract_id).is_some() {
+                return Err(SerializeError::DuplicateContractId { offset });
+            }
+
+            registry.register(contract_id);
+        }
+
+        Ok(registry)
+    }
+}
@@ -0,0 +1,3 @@
+pub mod account;
+pub mod context;
+pub mod contract;
@@ -0,0 +1,108 @@
+use super::opcode::{opcode_from_name, opcode_name, OP_PUSHDATA1, OP_PUSHDATA2, OP_PUSHDATA4};
+use crate::serialization::prefix::{read_pushdata, Prefix};
+
+type Bytes = Vec<u8>;
+
+#[derive(Debug, PartialEq)]
+pub enum ScriptError {
+    UnexpectedEndOfScript,
+    UnknownOpcode(String),
+    InvalidHex(String),
+    PushLengthError(String),
+}
+
+// Disassemble tapscript bytes into ASM.
+// Data pushes are rendered as <hex>. Pushes that are not in the minimal form produced by
+// `Prefix::prefix_pushdata` are prefixed with their push opcode, so that they assemble back
+// into the exact same bytes: e.g. `OP_PUSHBYTES_1 <05>` or `OP_PUSHDATA1 <aabb>`.
+pub fn disassemble(script: &[u8]) -> Result<String, ScriptError> {
+    let mut tokens = Vec::<String>::new();
+    let mut cursor: usize = 0;
+
+    while cursor < script.len() {
+        let opcode = script[cursor];
+
+        // Data pushes: OP_PUSHBYTES_1..OP_PUSHBYTES_75 and OP_PUSHDATA1/2/4
+        if !(0x01..=OP_PUSHDATA4).contains(&opcode) {
+            tokens.push(opcode_name(opcode));
+            cursor += 1;
+            continue;
+        }
+
+        let (data, len) =
+            read_pushdata(&script[cursor..]).map_err(|_| ScriptError::UnexpectedEndOfScript)?;
+        let data_hex = format!("<{}>", hex::encode(&data));
+
+        // Minimal pushes are rendered as bare data.
+        if data.prefix_pushdata() == script[cursor..cursor + len] {
+            tokens.push(data_hex);
+        } else {
+            tokens.push(opcode_name(opcode));
+            tokens.push(data_hex);
+        }
+
+        cursor += len;
+    }
+
+    Ok(tokens.join(" "))
+}
+
+// Assemble ASM into tapscript bytes.
+// Bare <hex> data is pushed in its minimal form with `Prefix::prefix_pushdata`.
+pub fn assemble(asm: &str) -> Result<Bytes, ScriptError> {
+    let mut script = Vec::<u8>::new();
+    let mut tokens = asm.split_whitespace();
+
+    while let Some(token) = tokens.next() {
+        if token.starts_with('<') {
+            script.extend(parse_data(token)?.prefix_pushdata());
+            continue;
+        }
+
+        if let Some(len) = token.strip_prefix("OP_PUSHBYTES_") {
+            let data = parse_data(tokens.next().unwrap_or_default())?;
+
+            match len.parse::<usize>() {
+                Ok(len @ 0x01..=0x4b) if len == data.len() => script.push(len as u8),
+                _ => return Err(ScriptError::PushLengthError(token.to_string())),
+            }
+
+            script.extend(data);
+            continue;
+        }
+
+        let opcode =
+            opcode_from_name(token).ok_or_else(|| ScriptError::UnknownOpcode(token.to_string()))?;
+
+        let len_bytes = match opcode {
+            OP_PUSHDATA1 => 1,
+            OP_PUSHDATA2 => 2,
+            OP_PUSHDATA4 => 4,
+            _ => {
+                script.push(opcode);
+                continue;
+            }
+        };
+
+        let data = parse_data(tokens.next().unwrap_or_default())?;
+
+        if (data.len() as u64) >> (len_bytes * 8) != 0 {
+            return Err(ScriptError::PushLengthError(token.to_string()));
+        }
+
+        script.push(opcode);
+        script.extend(&(data.len() as u64).to_le_bytes()[..len_bytes]);
+        script.extend(data);
+    }
+
+    Ok(script)
+}
+
+fn parse_data(token: &str) -> Result<Bytes, ScriptError> {
+    let hex_str = token
+        .strip_prefix('<')
+        .and_then(|token| token.strip_suffix('>'))
+        .ok_or_else(|| ScriptError::InvalidHex(token.to_string()))?;
+
+    hex::decode(hex_str).map_err(|_| ScriptError::InvalidHex(token.to_string()))
+}
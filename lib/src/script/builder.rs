@@ -0,0 +1,110 @@
+use super::opcode::{OP_CLTV, OP_CSV, OP_DROP, OP_EQUALVERIFY, OP_HASH160, OP_SHA256};
+use crate::serialization::{
+    cltv::AbsoluteLock,
+    csv::{to_cscript_num, to_csv_script_encode, CSVFlag, RelativeLock},
+    prefix::Prefix,
+    timelock::Timelock,
+};
+use musig2::secp256k1::XOnlyPublicKey;
+
+type Bytes = Vec<u8>;
+type Key = XOnlyPublicKey;
+
+#[derive(Clone, Default)]
+pub struct ScriptBuilder {
+    script: Bytes,
+}
+
+impl ScriptBuilder {
+    pub fn new() -> ScriptBuilder {
+        ScriptBuilder { script: Vec::new() }
+    }
+
+    pub fn push_opcode(mut self, opcode: u8) -> ScriptBuilder {
+        self.script.push(opcode);
+        self
+    }
+
+    // Push data in its minimal form.
+    pub fn push_slice(mut self, data: &[u8]) -> ScriptBuilder {
+        self.script.extend(data.to_vec().prefix_pushdata());
+        self
+    }
+
+    // Push a 32-byte x-only key.
+    pub fn push_key(self, key: &Key) -> ScriptBuilder {
+        self.push_slice(&key.serialize())
+    }
+
+    // Push an integer as a minimally-encoded CScriptNum.
+    pub fn push_int(self, value: i64) -> ScriptBuilder {
+        self.push_slice(&to_cscript_num(value))
+    }
+
+    // <timelock> OP_CHECKSEQUENCEVERIFY OP_DROP
+    pub fn push_csv(mut self, flag: CSVFlag) -> ScriptBuilder {
+        self.script.extend(to_csv_script_encode(flag));
+        self
+    }
+
+    // <blocks> OP_CHECKSEQUENCEVERIFY OP_DROP
+    pub fn push_csv_blocks(self, blocks: i64) -> ScriptBuilder {
+        self.push_int(blocks)
+            .push_opcode(OP_CSV)
+            .push_opcode(OP_DROP)
+    }
+
+    // <nSequence> OP_CHECKSEQUENCEVERIFY OP_DROP
+    pub fn push_relative_lock(self, lock: RelativeLock) -> ScriptBuilder {
+        self.push_int(lock.n_sequence() as i64)
+            .push_opcode(OP_CSV)
+            .push_opcode(OP_DROP)
+    }
+
+    // <nLockTime> OP_CHECKLOCKTIMEVERIFY OP_DROP
+    pub fn push_absolute_lock(self, lock: AbsoluteLock) -> ScriptBuilder {
+        self.push_int(lock.n_lock_time() as i64)
+            .push_opcode(OP_CLTV)
+            .push_opcode(OP_DROP)
+    }
+
+    // Relative or absolute timelock followed by OP_DROP.
+    pub fn push_timelock(self, timelock: Timelock) -> ScriptBuilder {
+        match timelock {
+            Timelock::Relative(lock) => self.push_relative_lock(lock),
+            Timelock::Absolute(lock) => self.push_absolute_lock(lock),
+        }
+    }
+
+    // OP_HASH160 <hash> OP_EQUALVERIFY
+    pub fn push_hash160_lock(self, hash: &[u8; 20]) -> ScriptBuilder {
+        self.push_opcode(OP_HASH160)
+            .push_slice(hash)
+            .push_opcode(OP_EQUALVERIFY)
+    }
+
+    // OP_SHA256 <hash> OP_EQUALVERIFY
+    pub fn push_sha256_lock(self, hash: &[u8; 32]) -> ScriptBuilder {
+        self.push_opcode(OP_SHA256)
+            .push_slice(hash)
+            .push_opcode(OP_EQUALVERIFY)
+    }
+
+    // Append raw, already-encoded script bytes.
+    pub fn push_raw(mut self, script: &[u8]) -> ScriptBuilder {
+        self.script.extend(script);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.script.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.script.is_empty()
+    }
+
+    pub fn into_script(self) -> Bytes {
+        self.script
+    }
+}
@@ -0,0 +1,3 @@
+pub mod asm;
+pub mod builder;
+pub mod opcode;
@@ -0,0 +1,178 @@
+use super::cpe::CPEDecodingError;
+use bit_vec::BitVec;
+
+type Bytes = Vec<u8>;
+
+// Bits are written most significant bit first, both within a byte and within an integer.
+// A bitstream is turned into bytes by appending zero-bits up to the next byte boundary;
+// the number of zero-bits appended is always in the range 0..=7.
+#[derive(Clone, Default)]
+pub struct BitWriter {
+    bits: BitVec,
+}
+
+impl BitWriter {
+    pub fn new() -> BitWriter {
+        BitWriter {
+            bits: BitVec::new(),
+        }
+    }
+
+    pub fn write_bit(&mut self, bit: bool) {
+        self.bits.push(bit);
+    }
+
+    pub fn write_bit_vec(&mut self, bit_vec: &BitVec) {
+        self.bits.extend(bit_vec);
+    }
+
+    // Write the lowest num_bits bits of value, most significant first.
+    pub fn write_uint(&mut self, value: u64, num_bits: usize) {
+        assert!(
+            num_bits <= 64,
+            "Cannot write more than 64 bits of an integer."
+        );
+
+        for i in (0..num_bits).rev() {
+            self.bits.push((value >> i) & 1 == 1);
+        }
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.write_uint(*byte as u64, 8);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    // Number of zero-bits needed to reach the next byte boundary (0..=7).
+    pub fn padding_len(&self) -> u8 {
+        ((8 - self.bits.len() % 8) % 8) as u8
+    }
+
+    pub fn to_bit_vec(&self) -> BitVec {
+        self.bits.clone()
+    }
+
+    // Zero-padded bytes of the bitstream.
+    pub fn to_bytes(&self) -> Bytes {
+        self.bits.to_bytes()
+    }
+}
+
+#[derive(Clone)]
+pub struct BitReader<'a> {
+    bytes: &'a [u8],
+    len: usize,
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    // Read all bits of the given bytes.
+    pub fn new(bytes: &'a [u8]) -> BitReader<'a> {
+        BitReader {
+            bytes,
+            len: bytes.len() * 8,
+            position: 0,
+        }
+    }
+
+    // Read the bytes of a zero-padded bitstream, excluding the trailing padding_len zero-bits.
+    pub fn from_padded(
+        bytes: &'a [u8],
+        padding_len: u8,
+    ) -> Result<BitReader<'a>, CPEDecodingError> {
+        if padding_len > 7 {
+            return Err(CPEDecodingError::PaddingError);
+        }
+
+        let len = (bytes.len() * 8)
+            .checked_sub(padding_len as usize)
+            .ok_or(CPEDecodingError::PaddingError)?;
+
+        let reader = BitReader {
+            bytes,
+            len,
+            position: 0,
+        };
+
+        // Padding bits must be zero.
+        for position in len..bytes.len() * 8 {
+            if reader.bit_at(position) {
+                return Err(CPEDecodingError::PaddingError);
+            }
+        }
+
+        Ok(reader)
+    }
+
+    fn bit_at(&self, position: usize) -> bool {
+        (self.bytes[position / 8] >> (7 - position % 8)) & 1 == 1
+    }
+
+    pub fn read_bit(&mut self) -> Result<bool, CPEDecodingError> {
+        if self.position >= self.len {
+            return Err(CPEDecodingError::EndOfBitsError);
+        }
+
+        let bit = self.bit_at(self.position);
+        self.position += 1;
+
+        Ok(bit)
+    }
+
+    pub fn read_bit_vec(&mut self, num_bits: usize) -> Result<BitVec, CPEDecodingError> {
+        let mut bit_vec = BitVec::new();
+
+        for _ in 0..num_bits {
+            bit_vec.push(self.read_bit()?);
+        }
+
+        Ok(bit_vec)
+    }
+
+    // Read num_bits bits as an integer, most significant first.
+    pub fn read_uint(&mut self, num_bits: usize) -> Result<u64, CPEDecodingError> {
+        assert!(
+            num_bits <= 64,
+            "Cannot read more than 64 bits of an integer."
+        );
+
+        let mut value: u64 = 0;
+
+        for _ in 0..num_bits {
+            value = (value << 1) | self.read_bit()? as u64;
+        }
+
+        Ok(value)
+    }
+
+    pub fn read_bytes(&mut self, num_bytes: usize) -> Result<Bytes, CPEDecodingError> {
+        let mut bytes = Vec::<u8>::with_capacity(num_bytes);
+
+        for _ in 0..num_bytes {
+            bytes.push(self.read_uint(8)? as u8);
+        }
+
+        Ok(bytes)
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.len - self.position
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.remaining() == 0
+    }
+}
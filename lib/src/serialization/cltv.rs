@@ -0,0 +1,132 @@
+use super::{
+    csv::{from_cscript_num, timelock_operand, to_cscript_num, CSVError},
+    prefix::Prefix,
+};
+use crate::script::opcode::{OP_CLTV, OP_DROP};
+
+type Bytes = Vec<u8>;
+
+// nLockTime values below this threshold are block heights, values at or above it are
+// UNIX timestamps.
+// https://github.com/bitcoin/bips/blob/master/bip-0065.mediawiki
+pub const LOCKTIME_THRESHOLD: u32 = 500_000_000;
+
+// OP_CHECKLOCKTIMEVERIFY operands may be up to 5 bytes long.
+const CLTV_CSCRIPT_NUM_MAX_LEN: usize = 5;
+
+#[derive(Debug, PartialEq)]
+pub enum CLTVError {
+    CScriptNumOverflow,
+    NonMinimalCScriptNum,
+    NegativeLock,
+    PushParseError,
+}
+
+impl From<CSVError> for CLTVError {
+    fn from(error: CSVError) -> CLTVError {
+        match error {
+            CSVError::NonMinimalCScriptNum => CLTVError::NonMinimalCScriptNum,
+            CSVError::NegativeLock => CLTVError::NegativeLock,
+            CSVError::PushParseError => CLTVError::PushParseError,
+            _ => CLTVError::CScriptNumOverflow,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AbsoluteLock {
+    // Block height
+    Height(u32),
+    // UNIX timestamp (median time past)
+    Time(u32),
+}
+
+impl AbsoluteLock {
+    pub fn from_height(height: u32) -> Option<AbsoluteLock> {
+        match height < LOCKTIME_THRESHOLD {
+            true => Some(AbsoluteLock::Height(height)),
+            false => None,
+        }
+    }
+
+    pub fn from_timestamp(timestamp: u32) -> Option<AbsoluteLock> {
+        match timestamp >= LOCKTIME_THRESHOLD {
+            true => Some(AbsoluteLock::Time(timestamp)),
+            false => None,
+        }
+    }
+
+    // Every nLockTime value is either a height or a timestamp.
+    pub fn from_n_lock_time(n_lock_time: u32) -> AbsoluteLock {
+        match n_lock_time < LOCKTIME_THRESHOLD {
+            true => AbsoluteLock::Height(n_lock_time),
+            false => AbsoluteLock::Time(n_lock_time),
+        }
+    }
+
+    pub fn n_lock_time(&self) -> u32 {
+        match self {
+            AbsoluteLock::Height(height) => *height,
+            AbsoluteLock::Time(timestamp) => *timestamp,
+        }
+    }
+
+    // Whether a transaction with the given nLockTime satisfies this lock.
+    // Heights and timestamps cannot be compared against each other.
+    pub fn is_satisfied_by(&self, n_lock_time: u32) -> bool {
+        match (self, AbsoluteLock::from_n_lock_time(n_lock_time)) {
+            (AbsoluteLock::Height(lock), AbsoluteLock::Height(height)) => height >= *lock,
+            (AbsoluteLock::Time(lock), AbsoluteLock::Time(timestamp)) => timestamp >= *lock,
+            _ => false,
+        }
+    }
+
+    // 4-byte little-endian nLockTime field.
+    pub fn to_n_lock_time_bytes(&self) -> Bytes {
+        self.n_lock_time().to_le_bytes().to_vec()
+    }
+
+    pub fn from_n_lock_time_bytes(bytes: &[u8]) -> Option<AbsoluteLock> {
+        let n_lock_time_bytes: [u8; 4] = bytes.try_into().ok()?;
+        Some(AbsoluteLock::from_n_lock_time(u32::from_le_bytes(
+            n_lock_time_bytes,
+        )))
+    }
+
+    // Minimal CScriptNum of the nLockTime value.
+    pub fn to_cscript_num(&self) -> Bytes {
+        to_cscript_num(self.n_lock_time() as i64)
+    }
+
+    pub fn from_cscript_num(bytes: &[u8]) -> Result<AbsoluteLock, CLTVError> {
+        let value = from_cscript_num(bytes, CLTV_CSCRIPT_NUM_MAX_LEN)?;
+
+        if value < 0 {
+            return Err(CLTVError::NegativeLock);
+        }
+
+        let n_lock_time = u32::try_from(value).map_err(|_| CLTVError::CScriptNumOverflow)?;
+
+        Ok(AbsoluteLock::from_n_lock_time(n_lock_time))
+    }
+
+    // <locktime> OP_CHECKLOCKTIMEVERIFY OP_DROP
+    pub fn to_cltv_script(&self) -> Bytes {
+        let mut encoded = self.to_cscript_num().prefix_pushdata();
+
+        // OP_CHECKLOCKTIMEVERIFY
+        encoded.push(OP_CLTV);
+
+        // OP_DROP
+        encoded.push(OP_DROP);
+
+        encoded
+    }
+
+    // Parse the lock from a <locktime> OP_CHECKLOCKTIMEVERIFY OP_DROP fragment.
+    pub fn from_cltv_script(script: &[u8]) -> Result<AbsoluteLock, CLTVError> {
+        let operand = timelock_operand(script, OP_CLTV, CLTV_CSCRIPT_NUM_MAX_LEN)?;
+
+        AbsoluteLock::from_cscript_num(&operand)
+    }
+}
@@ -1,9 +1,76 @@
+use super::bits::{BitReader, BitWriter};
+use crate::valtype::{
+    account::Account,
+    contract::Contract,
+    value::{LongVal, ShortVal},
+};
 use bit_vec::BitVec;
+use musig2::secp256k1::XOnlyPublicKey;
+
+type Key = XOnlyPublicKey;
+
+#[derive(Debug, PartialEq)]
+pub enum CPEDecodingError {
+    EndOfBitsError,
+    PaddingError,
+    KeyParseError,
+    EntryTypeError,
+    AccountIndexError(u32),
+    ContractIndexError(u32),
+    CommonIndexError(u8),
+    CommonTableLengthError(u8),
+}
 
 pub trait CompactPayloadEncoding {
-    fn to_cpe(&self) -> BitVec;
+    fn write_cpe(&self, writer: &mut BitWriter);
+
+    fn to_cpe(&self) -> BitVec {
+        let mut writer = BitWriter::new();
+        self.write_cpe(&mut writer);
+        writer.to_bit_vec()
+    }
 }
 
+pub trait CompactPayloadDecoding {
+    fn from_cpe(
+        reader: &mut BitReader,
+        context: &impl CPEContext,
+    ) -> Result<Self, CPEDecodingError>
+    where
+        Self: Sized;
+}
+
+// Resolves compact indexes and common indexes back into their full values during decoding.
+// Lookups default to none; decoding fails with an index error if a reference cannot be resolved.
+pub trait CPEContext {
+    fn account_key(&self, _account_index: u32) -> Option<Key> {
+        None
+    }
+
+    fn contract_id(&self, _contract_index: u32) -> Option<[u8; 32]> {
+        None
+    }
+
+    fn common_account(&self, _common_index: u8) -> Option<Account> {
+        None
+    }
+
+    fn common_contract(&self, _common_index: u8) -> Option<Contract> {
+        None
+    }
+
+    fn common_short_val(&self, _common_index: u8) -> Option<ShortVal> {
+        None
+    }
+
+    fn common_long_val(&self, _common_index: u8) -> Option<LongVal> {
+        None
+    }
+}
+
+// Context-free decoding: compact and common references fail to resolve.
+impl CPEContext for () {}
+
 pub trait CommonIndex {
     fn from_u8_common_index(common_index: &u8) -> BitVec;
     fn to_u8_common_index(&self) -> u8;
@@ -1,7 +1,218 @@
 use super::prefix::Prefix;
+use crate::script::opcode::{OP_0, OP_1, OP_16, OP_1NEGATE, OP_CSV, OP_DROP};
 
 type Bytes = Vec<u8>;
 
+// BIP-68 relative lock-time flags and mask.
+// https://github.com/bitcoin/bips/blob/master/bip-0068.mediawiki
+const SEQUENCE_LOCKTIME_DISABLE_FLAG: u32 = 1 << 31;
+const SEQUENCE_LOCKTIME_TYPE_FLAG: u32 = 1 << 22;
+const SEQUENCE_LOCKTIME_MASK: u32 = 0x0000ffff;
+
+// Time-based relative locks are in units of 512 seconds.
+const SEQUENCE_LOCKTIME_GRANULARITY: u32 = 512;
+
+// OP_CHECKSEQUENCEVERIFY operands may be up to 5 bytes long.
+const CSV_CSCRIPT_NUM_MAX_LEN: usize = 5;
+
+#[derive(Debug, PartialEq)]
+pub enum CSVError {
+    CScriptNumOverflow,
+    NonMinimalCScriptNum,
+    NegativeLock,
+    DisabledLock,
+    UnknownLockBits,
+    InvalidLength,
+    PushParseError,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RelativeLock {
+    // Number of blocks
+    Blocks(u16),
+    // Number of 512-second intervals
+    Time(u16),
+}
+
+impl RelativeLock {
+    pub fn from_blocks(blocks: u16) -> RelativeLock {
+        RelativeLock::Blocks(blocks)
+    }
+
+    pub fn from_days(days: u16) -> Option<RelativeLock> {
+        Some(RelativeLock::Blocks(days.checked_mul(144)?))
+    }
+
+    // Seconds are rounded up to the next 512-second interval.
+    pub fn from_seconds(seconds: u32) -> Option<RelativeLock> {
+        let intervals = seconds.div_ceil(SEQUENCE_LOCKTIME_GRANULARITY);
+        Some(RelativeLock::Time(u16::try_from(intervals).ok()?))
+    }
+
+    pub fn n_sequence(&self) -> u32 {
+        match self {
+            RelativeLock::Blocks(blocks) => *blocks as u32,
+            RelativeLock::Time(intervals) => SEQUENCE_LOCKTIME_TYPE_FLAG | *intervals as u32,
+        }
+    }
+
+    pub fn from_n_sequence(n_sequence: u32) -> Result<RelativeLock, CSVError> {
+        if n_sequence & SEQUENCE_LOCKTIME_DISABLE_FLAG != 0 {
+            return Err(CSVError::DisabledLock);
+        }
+
+        // Bits other than the type flag and the value are not interpreted; reject them
+        // so that the lock re-encodes to the same value.
+        if n_sequence & !(SEQUENCE_LOCKTIME_TYPE_FLAG | SEQUENCE_LOCKTIME_MASK) != 0 {
+            return Err(CSVError::UnknownLockBits);
+        }
+
+        let value = (n_sequence & SEQUENCE_LOCKTIME_MASK) as u16;
+
+        match n_sequence & SEQUENCE_LOCKTIME_TYPE_FLAG {
+            0 => Ok(RelativeLock::Blocks(value)),
+            _ => Ok(RelativeLock::Time(value)),
+        }
+    }
+
+    // 4-byte little-endian nSequence field.
+    pub fn to_n_sequence_bytes(&self) -> Bytes {
+        self.n_sequence().to_le_bytes().to_vec()
+    }
+
+    pub fn from_n_sequence_bytes(bytes: &[u8]) -> Result<RelativeLock, CSVError> {
+        let n_sequence_bytes: [u8; 4] = bytes.try_into().map_err(|_| CSVError::InvalidLength)?;
+        RelativeLock::from_n_sequence(u32::from_le_bytes(n_sequence_bytes))
+    }
+
+    // Minimal CScriptNum of the nSequence value.
+    pub fn to_cscript_num(&self) -> Bytes {
+        to_cscript_num(self.n_sequence() as i64)
+    }
+
+    pub fn from_cscript_num(bytes: &[u8]) -> Result<RelativeLock, CSVError> {
+        let value = from_cscript_num(bytes, CSV_CSCRIPT_NUM_MAX_LEN)?;
+
+        if value < 0 {
+            return Err(CSVError::NegativeLock);
+        }
+
+        if value > u32::MAX as i64 {
+            return Err(CSVError::CScriptNumOverflow);
+        }
+
+        RelativeLock::from_n_sequence(value as u32)
+    }
+
+    // <timelock> OP_CHECKSEQUENCEVERIFY OP_DROP
+    pub fn to_csv_script(&self) -> Bytes {
+        let mut encoded = self.to_cscript_num().prefix_pushdata();
+
+        // OP_CHECKSEQUENCEVERIFY
+        encoded.push(OP_CSV);
+
+        // OP_DROP
+        encoded.push(OP_DROP);
+
+        encoded
+    }
+
+    // Parse the lock from a <timelock> OP_CHECKSEQUENCEVERIFY OP_DROP fragment.
+    pub fn from_csv_script(script: &[u8]) -> Result<RelativeLock, CSVError> {
+        let operand = timelock_operand(script, OP_CSV, CSV_CSCRIPT_NUM_MAX_LEN)?;
+
+        RelativeLock::from_cscript_num(&operand)
+    }
+}
+
+// Operand of a <timelock> OP_CHECKSEQUENCEVERIFY/OP_CHECKLOCKTIMEVERIFY OP_DROP fragment.
+pub fn timelock_operand(script: &[u8], opcode: u8, max_len: usize) -> Result<Bytes, CSVError> {
+    // <opcode> OP_DROP
+    let push = match script.split_last_chunk::<2>() {
+        Some((push, [last_opcode, OP_DROP])) if *last_opcode == opcode => push,
+        _ => return Err(CSVError::PushParseError),
+    };
+
+    let operand = match push {
+        [OP_0] => vec![],
+        [OP_1NEGATE] => vec![0x81],
+        [small @ OP_1..=OP_16] => vec![small - (OP_1 - 1)],
+        // Direct push
+        [len, data @ ..] if *len as usize == data.len() && data.len() <= max_len => data.to_vec(),
+        _ => return Err(CSVError::PushParseError),
+    };
+
+    // The operand must be pushed minimally.
+    if operand.prefix_pushdata() != push {
+        return Err(CSVError::PushParseError);
+    }
+
+    Ok(operand)
+}
+
+// Minimal little-endian sign-magnitude encoding of script integers.
+// https://github.com/bitcoin/bitcoin/blob/master/src/script/script.h#L356
+pub fn to_cscript_num(value: i64) -> Bytes {
+    let mut bytes = Vec::<u8>::new();
+
+    if value == 0 {
+        return bytes;
+    }
+
+    let negative = value < 0;
+    let mut abs_value = value.unsigned_abs();
+
+    while abs_value > 0 {
+        bytes.push((abs_value & 0xff) as u8);
+        abs_value >>= 8;
+    }
+
+    // If the most significant byte has its sign bit set, add an extra byte for the sign.
+    // Otherwise, set the sign bit of the most significant byte if negative.
+    let last = bytes.len() - 1;
+    if bytes[last] & 0x80 != 0 {
+        bytes.push(if negative { 0x80 } else { 0x00 });
+    } else if negative {
+        bytes[last] |= 0x80;
+    }
+
+    bytes
+}
+
+// Decode a minimally-encoded CScriptNum of at most max_len bytes.
+pub fn from_cscript_num(bytes: &[u8], max_len: usize) -> Result<i64, CSVError> {
+    if bytes.len() > max_len || bytes.len() > 8 {
+        return Err(CSVError::CScriptNumOverflow);
+    }
+
+    let last = match bytes.last() {
+        Some(last) => *last,
+        None => return Ok(0),
+    };
+
+    // The most significant byte may only be a sign byte if the next byte needs its sign bit.
+    if last & 0x7f == 0 && (bytes.len() == 1 || bytes[bytes.len() - 2] & 0x80 == 0) {
+        return Err(CSVError::NonMinimalCScriptNum);
+    }
+
+    let mut abs_value: u64 = 0;
+    for (i, byte) in bytes.iter().enumerate() {
+        let byte = if i == bytes.len() - 1 {
+            byte & 0x7f
+        } else {
+            *byte
+        };
+        abs_value |= (byte as u64) << (8 * i);
+    }
+
+    let abs_value = i64::try_from(abs_value).map_err(|_| CSVError::CScriptNumOverflow)?;
+
+    match last & 0x80 {
+        0 => Ok(abs_value),
+        _ => Ok(-abs_value),
+    }
+}
+
 pub enum CSVFlag {
     CSVBlock,
     CSVHour,
@@ -14,6 +225,7 @@ pub enum CSVFlag {
     CSVSixMonths,
     CSVYear,
     Days(u8),
+    Lock(RelativeLock),
 }
 
 pub fn days_to_bytes(days: u8, cscript_num: bool) -> Bytes {
@@ -70,6 +282,7 @@ pub fn to_n_sequence_encode(flag: CSVFlag) -> Bytes {
         CSVFlag::CSVSixMonths => encoded.extend(vec![0x40, 0x65, 0x00, 0x00]),
         CSVFlag::CSVYear => encoded.extend(vec![0x50, 0xcd, 0x00, 0x00]),
         CSVFlag::Days(days) => encoded.extend(pad_four(days_to_bytes(days, false))),
+        CSVFlag::Lock(lock) => encoded.extend(lock.to_n_sequence_bytes()),
     }
 
     encoded
@@ -90,13 +303,14 @@ pub fn to_csv_script_encode(flag: CSVFlag) -> Bytes {
         CSVFlag::CSVSixMonths => encoded.extend(vec![0x02, 0x40, 0x65]),
         CSVFlag::CSVYear => encoded.extend(vec![0x03, 0x50, 0xcd, 0x00]),
         CSVFlag::Days(days) => encoded.extend(&days_to_bytes(days, true).prefix_pushdata()),
+        CSVFlag::Lock(lock) => encoded.extend(lock.to_cscript_num().prefix_pushdata()),
     }
 
     // OP_CHECKSEQUENCEVERIFY
-    encoded.push(0xb2);
+    encoded.push(OP_CSV);
 
     // OP_DROP
-    encoded.push(0x75);
+    encoded.push(OP_DROP);
 
     encoded
 }
@@ -1,6 +1,11 @@
+pub mod bits;
+pub mod cltv;
 pub mod cpe;
 pub mod csv;
 pub mod prefix;
 pub mod push;
+#[cfg(feature = "serde")]
+pub mod serde_hex;
 pub mod serialize;
-pub mod sighash;
\ No newline at end of file
+pub mod sighash;
+pub mod timelock;
\ No newline at end of file
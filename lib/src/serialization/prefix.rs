@@ -1,5 +1,13 @@
 type Bytes = Vec<u8>;
 
+#[derive(Debug, PartialEq)]
+pub enum PrefixError {
+    UnexpectedEndOfBytes,
+    NonCanonicalCompactSize,
+    NonPushOpcode(u8),
+    ChunkError,
+}
+
 pub trait Prefix {
     // Interpret bytes as stack push and prefix them with OP_PUSHDATA.
     // https://en.bitcoin.it/wiki/Script
@@ -113,3 +121,146 @@ impl Prefix for Bytes {
         bytes
     }
 }
+
+// Encode an integer as CompactSize.
+pub fn to_compact_size(value: u64) -> Bytes {
+    let mut bytes = Vec::<u8>::new();
+
+    match value {
+        0..=252 => bytes.push(value as u8),
+        253..=65535 => {
+            bytes.push(0xfd);
+            bytes.extend((value as u16).to_le_bytes());
+        }
+        65536..=4294967295 => {
+            bytes.push(0xfe);
+            bytes.extend((value as u32).to_le_bytes());
+        }
+        _ => {
+            bytes.push(0xff);
+            bytes.extend(value.to_le_bytes());
+        }
+    }
+
+    bytes
+}
+
+// Parse a CompactSize integer, returning the value along with the number of bytes read.
+// Values that could have been encoded in a shorter form are rejected.
+// https://github.com/bitcoin/bitcoin/blob/master/src/serialize.h#L339
+pub fn read_compact_size(bytes: &[u8]) -> Result<(u64, usize), PrefixError> {
+    let (len_bytes, min_value) = match bytes.first() {
+        None => return Err(PrefixError::UnexpectedEndOfBytes),
+        Some(byte @ 0x00..=0xfc) => return Ok((*byte as u64, 1)),
+        Some(0xfd) => (2, 0xfd),
+        Some(0xfe) => (4, 0x10000),
+        Some(_) => (8, 0x100000000),
+    };
+
+    let value = read_le(bytes, 1, len_bytes)?;
+
+    if value < min_value {
+        return Err(PrefixError::NonCanonicalCompactSize);
+    }
+
+    Ok((value, 1 + len_bytes))
+}
+
+// Parse CompactSize-prefixed data, returning the data along with the number of bytes read.
+pub fn read_compact_size_prefixed(bytes: &[u8]) -> Result<(Bytes, usize), PrefixError> {
+    let (data_len, prefix_len) = read_compact_size(bytes)?;
+    let data = take(bytes, prefix_len, data_len)?;
+
+    Ok((data.to_vec(), prefix_len + data.len()))
+}
+
+// Parse a single push, returning the data along with the number of bytes read.
+// OP_0, OP_1NEGATE and OP_1..OP_16 are read back as the single byte `prefix_pushdata`
+// encodes them from. Non-minimal pushes are accepted as is.
+pub fn read_pushdata(bytes: &[u8]) -> Result<(Bytes, usize), PrefixError> {
+    let opcode = *bytes.first().ok_or(PrefixError::UnexpectedEndOfBytes)?;
+
+    let (data_len, prefix_len) = match opcode {
+        // OP_0
+        0x00 => return Ok((vec![0x00], 1)),
+        // OP_PUSHBYTES_1..OP_PUSHBYTES_75
+        0x01..=0x4b => (opcode as u64, 1),
+        // OP_PUSHDATA1
+        0x4c => (read_le(bytes, 1, 1)?, 2),
+        // OP_PUSHDATA2
+        0x4d => (read_le(bytes, 1, 2)?, 3),
+        // OP_PUSHDATA4
+        0x4e => (read_le(bytes, 1, 4)?, 5),
+        // OP_1NEGATE
+        0x4f => return Ok((vec![0x81], 1)),
+        // OP_1..OP_16
+        0x51..=0x60 => return Ok((vec![opcode - 0x50], 1)),
+        _ => return Err(PrefixError::NonPushOpcode(opcode)),
+    };
+
+    let data = take(bytes, prefix_len, data_len)?;
+
+    Ok((data.to_vec(), prefix_len + data.len()))
+}
+
+// Iterate over all pushes of a push-only script.
+pub fn pushes(script: &[u8]) -> Pushes<'_> {
+    Pushes {
+        script,
+        position: 0,
+        failed: false,
+    }
+}
+
+pub struct Pushes<'a> {
+    script: &'a [u8],
+    position: usize,
+    failed: bool,
+}
+
+impl Pushes<'_> {
+    // Number of bytes read so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl Iterator for Pushes<'_> {
+    type Item = Result<Bytes, PrefixError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.position >= self.script.len() {
+            return None;
+        }
+
+        match read_pushdata(&self.script[self.position..]) {
+            Ok((data, len)) => {
+                self.position += len;
+                Some(Ok(data))
+            }
+            Err(error) => {
+                // Stop at the first malformed push.
+                self.failed = true;
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+fn take(bytes: &[u8], start: usize, len: u64) -> Result<&[u8], PrefixError> {
+    let end = usize::try_from(len)
+        .ok()
+        .and_then(|len| start.checked_add(len))
+        .ok_or(PrefixError::UnexpectedEndOfBytes)?;
+
+    bytes
+        .get(start..end)
+        .ok_or(PrefixError::UnexpectedEndOfBytes)
+}
+
+fn read_le(bytes: &[u8], start: usize, len: usize) -> Result<u64, PrefixError> {
+    let mut le_bytes = [0x00u8; 8];
+    le_bytes[..len].copy_from_slice(take(bytes, start, len as u64)?);
+
+    Ok(u64::from_le_bytes(le_bytes))
+}
@@ -1,4 +1,4 @@
-use super::prefix::Prefix;
+use super::prefix::{pushes, read_compact_size_prefixed, Prefix, PrefixError};
 
 type Bytes = Vec<u8>;
 
@@ -46,6 +46,41 @@ impl Push for Bytes {
     }
 }
 
+// Rejoin the chunks of an `as_multi_pushdata_push` encoding.
+// The encoding must be exactly what `as_multi_pushdata_push` produces for the rejoined data.
+pub fn from_multi_pushdata_push(bytes: &[u8]) -> Result<Bytes, PrefixError> {
+    let mut data = Vec::<u8>::new();
+
+    for push in pushes(bytes) {
+        data.extend(push?);
+    }
+
+    if data.as_multi_pushdata_push() != bytes {
+        return Err(PrefixError::ChunkError);
+    }
+
+    Ok(data)
+}
+
+// Rejoin the chunks of an `as_multi_witness_push` encoding.
+// The encoding must be exactly what `as_multi_witness_push` produces for the rejoined data.
+pub fn from_multi_witness_push(bytes: &[u8], standard: bool) -> Result<Bytes, PrefixError> {
+    let mut data = Vec::<u8>::new();
+    let mut cursor: usize = 0;
+
+    while cursor < bytes.len() {
+        let (chunk, len) = read_compact_size_prefixed(&bytes[cursor..])?;
+        data.extend(chunk);
+        cursor += len;
+    }
+
+    if data.as_multi_witness_push(standard) != bytes {
+        return Err(PrefixError::ChunkError);
+    }
+
+    Ok(data)
+}
+
 fn chunkify(data: &Bytes, flag: &PushFlag) -> Vec<Bytes> {
     let mut chunks: Vec<Bytes> = Vec::<Bytes>::new();
 
@@ -0,0 +1,127 @@
+// Serde helpers for byte fields.
+// Human-readable formats (e.g. JSON) get lowercase hex strings, binary formats get raw bytes.
+
+use serde::{
+    de::{Error, SeqAccess, Visitor},
+    Deserializer, Serializer,
+};
+use std::fmt;
+
+type Bytes = Vec<u8>;
+
+fn serialize_bytes<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    match serializer.is_human_readable() {
+        true => serializer.serialize_str(&hex::encode(bytes)),
+        false => serializer.serialize_bytes(bytes),
+    }
+}
+
+fn deserialize_bytes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Bytes, D::Error> {
+    match deserializer.is_human_readable() {
+        true => deserializer.deserialize_str(HexVisitor),
+        false => deserializer.deserialize_byte_buf(BytesVisitor),
+    }
+}
+
+struct HexVisitor;
+
+impl Visitor<'_> for HexVisitor {
+    type Value = Bytes;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a hex string")
+    }
+
+    fn visit_str<E: Error>(self, value: &str) -> Result<Bytes, E> {
+        hex::decode(value).map_err(E::custom)
+    }
+}
+
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Bytes;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a byte array")
+    }
+
+    fn visit_bytes<E: Error>(self, value: &[u8]) -> Result<Bytes, E> {
+        Ok(value.to_vec())
+    }
+
+    fn visit_byte_buf<E: Error>(self, value: Bytes) -> Result<Bytes, E> {
+        Ok(value)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Bytes, A::Error> {
+        let mut bytes = Vec::<u8>::new();
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+// Variable-length bytes
+pub mod bytes {
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        super::serialize_bytes(bytes, serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        super::deserialize_bytes(deserializer)
+    }
+}
+
+// Fixed 32 bytes
+pub mod bytes32 {
+    use serde::{de::Error, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8; 32], serializer: S) -> Result<S::Ok, S::Error> {
+        super::serialize_bytes(bytes, serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<[u8; 32], D::Error> {
+        let bytes = super::deserialize_bytes(deserializer)?;
+        bytes
+            .try_into()
+            .map_err(|_| D::Error::custom("expected 32 bytes"))
+    }
+}
+
+// 32-byte x-only key
+pub mod key {
+    use musig2::secp256k1::XOnlyPublicKey;
+    use serde::{de::Error, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        key: &XOnlyPublicKey,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        super::serialize_bytes(&key.serialize(), serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<XOnlyPublicKey, D::Error> {
+        let bytes = super::deserialize_bytes(deserializer)?;
+        XOnlyPublicKey::from_slice(&bytes).map_err(D::Error::custom)
+    }
+}
+
+// Key parity as 0 (even) or 1 (odd)
+pub mod parity {
+    use musig2::secp256k1::Parity;
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(parity: &Parity, serializer: S) -> Result<S::Ok, S::Error> {
+        parity.to_u8().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Parity, D::Error> {
+        Parity::from_u8(u8::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
@@ -1,12 +1,222 @@
+use musig2::secp256k1::XOnlyPublicKey;
+use std::io::{ErrorKind, Read, Write};
+
 type Bytes = Vec<u8>;
+type Key = XOnlyPublicKey;
 
-#[derive(Debug)]
+// Offsets are byte positions from the start of the serialization.
+#[derive(Debug, PartialEq)]
 pub enum SerializeError {
-    KeyParseError,
-    EntryTypeError,
+    UnexpectedEof { offset: usize },
+    TrailingBytes { offset: usize },
+    InvalidKey { offset: usize },
+    EntryTypeError { offset: usize, entry_type: u8 },
+    DuplicateKey { offset: usize },
+    DuplicateContractId { offset: usize },
+    NonCanonicalCompactSize { offset: usize },
+    NonCanonicalEncoding { offset: usize },
+    InvalidFlag { offset: usize, flag: u8 },
+    IoError(ErrorKind),
 }
 
 pub trait Serialize {
-    fn serialize(&self) -> Bytes;
-    fn from_bytes(bytes: Bytes) -> Result<Self, SerializeError> where Self: Sized;
-}
\ No newline at end of file
+    fn write_to<W: Write>(&self, writer: &mut W) -> Result<(), SerializeError>;
+
+    fn read_from<R: Read>(reader: &mut ByteReader<R>) -> Result<Self, SerializeError>
+    where
+        Self: Sized;
+
+    fn serialize(&self) -> Bytes {
+        let mut bytes = Vec::<u8>::new();
+        self.write_to(&mut bytes)
+            .expect("Writing into a byte vector cannot fail.");
+        bytes
+    }
+
+    // Deserialize from exactly the given bytes; leftover bytes are an error.
+    fn from_bytes(bytes: &[u8]) -> Result<Self, SerializeError>
+    where
+        Self: Sized,
+    {
+        let mut reader = ByteReader::new(bytes);
+        let value = Self::read_from(&mut reader)?;
+        reader.expect_end()?;
+
+        Ok(value)
+    }
+}
+
+// Reads from an underlying reader while keeping track of the byte offset for error reporting.
+pub struct ByteReader<R: Read> {
+    inner: R,
+    offset: usize,
+    // Byte read ahead by peek, not yet consumed.
+    peeked: Option<u8>,
+}
+
+impl<R: Read> ByteReader<R> {
+    pub fn new(inner: R) -> ByteReader<R> {
+        ByteReader {
+            inner,
+            offset: 0,
+            peeked: None,
+        }
+    }
+
+    // Number of bytes read so far.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), SerializeError> {
+        let mut filled: usize = 0;
+
+        if !buf.is_empty() {
+            if let Some(byte) = self.peeked.take() {
+                buf[0] = byte;
+                filled = 1;
+            }
+        }
+
+        while filled < buf.len() {
+            match self.inner.read(&mut buf[filled..]) {
+                Ok(0) => {
+                    self.offset += filled;
+                    return Err(SerializeError::UnexpectedEof {
+                        offset: self.offset,
+                    });
+                }
+                Ok(n) => filled += n,
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(error) => return Err(SerializeError::IoError(error.kind())),
+            }
+        }
+
+        self.offset += filled;
+        Ok(())
+    }
+
+    pub fn read_array<const N: usize>(&mut self) -> Result<[u8; N], SerializeError> {
+        let mut array = [0x00u8; N];
+        self.read_exact(&mut array)?;
+        Ok(array)
+    }
+
+    pub fn read_bytes(&mut self, len: usize) -> Result<Bytes, SerializeError> {
+        // Read in bounded steps so that a bogus length cannot trigger a large allocation.
+        let mut bytes = Vec::<u8>::new();
+        let mut chunk = [0x00u8; 256];
+
+        while bytes.len() < len {
+            let step = (len - bytes.len()).min(chunk.len());
+            self.read_exact(&mut chunk[..step])?;
+            bytes.extend(&chunk[..step]);
+        }
+
+        Ok(bytes)
+    }
+
+    // Next byte without consuming it.
+    pub fn peek_u8(&mut self) -> Result<u8, SerializeError> {
+        if let Some(byte) = self.peeked {
+            return Ok(byte);
+        }
+
+        let mut byte = [0x00u8; 1];
+
+        loop {
+            match self.inner.read(&mut byte) {
+                Ok(0) => {
+                    return Err(SerializeError::UnexpectedEof {
+                        offset: self.offset,
+                    })
+                }
+                Ok(_) => break,
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(error) => return Err(SerializeError::IoError(error.kind())),
+            }
+        }
+
+        self.peeked = Some(byte[0]);
+        Ok(byte[0])
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, SerializeError> {
+        Ok(self.read_array::<1>()?[0])
+    }
+
+    pub fn read_u32_le(&mut self) -> Result<u32, SerializeError> {
+        Ok(u32::from_le_bytes(self.read_array::<4>()?))
+    }
+
+    pub fn read_u64_le(&mut self) -> Result<u64, SerializeError> {
+        Ok(u64::from_le_bytes(self.read_array::<8>()?))
+    }
+
+    // CompactSize integer; values that could have been encoded in a shorter form are rejected.
+    pub fn read_compact_size(&mut self) -> Result<u64, SerializeError> {
+        let offset = self.offset;
+
+        let (value, min_value) = match self.read_u8()? {
+            byte @ 0x00..=0xfc => return Ok(byte as u64),
+            0xfd => (u16::from_le_bytes(self.read_array::<2>()?) as u64, 0xfd),
+            0xfe => (self.read_u32_le()? as u64, 0x10000),
+            _ => (self.read_u64_le()?, 0x100000000),
+        };
+
+        if value < min_value {
+            return Err(SerializeError::NonCanonicalCompactSize { offset });
+        }
+
+        Ok(value)
+    }
+
+    // CompactSize-prefixed bytes.
+    pub fn read_compact_size_prefixed(&mut self) -> Result<Bytes, SerializeError> {
+        let len = self.read_compact_size()?;
+        let len = usize::try_from(len).map_err(|_| SerializeError::UnexpectedEof {
+            offset: self.offset,
+        })?;
+
+        self.read_bytes(len)
+    }
+
+    // 32-byte x-only key.
+    pub fn read_key(&mut self) -> Result<Key, SerializeError> {
+        let offset = self.offset;
+        let key_bytes = self.read_array::<32>()?;
+
+        Key::from_slice(&key_bytes).map_err(|_| SerializeError::InvalidKey { offset })
+    }
+
+    // Expect the underlying reader to be exhausted.
+    pub fn expect_end(&mut self) -> Result<(), SerializeError> {
+        if self.peeked.is_some() {
+            return Err(SerializeError::TrailingBytes {
+                offset: self.offset,
+            });
+        }
+
+        let mut byte = [0x00u8; 1];
+
+        loop {
+            match self.inner.read(&mut byte) {
+                Ok(0) => return Ok(()),
+                Ok(_) => {
+                    return Err(SerializeError::TrailingBytes {
+                        offset: self.offset,
+                    })
+                }
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(error) => return Err(SerializeError::IoError(error.kind())),
+            }
+        }
+    }
+}
+
+// Write all bytes, mapping I/O failures.
+pub fn write_all<W: Write>(writer: &mut W, bytes: &[u8]) -> Result<(), SerializeError> {
+    writer
+        .write_all(bytes)
+        .map_err(|error| SerializeError::IoError(error.kind()))
+}
@@ -0,0 +1,33 @@
+use super::{cltv::AbsoluteLock, csv::RelativeLock};
+
+type Bytes = Vec<u8>;
+
+// Timelock of an exit or sweep leaf.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Timelock {
+    // <nSequence> OP_CHECKSEQUENCEVERIFY OP_DROP
+    Relative(RelativeLock),
+    // <nLockTime> OP_CHECKLOCKTIMEVERIFY OP_DROP
+    Absolute(AbsoluteLock),
+}
+
+impl Timelock {
+    pub fn to_script(&self) -> Bytes {
+        match self {
+            Timelock::Relative(lock) => lock.to_csv_script(),
+            Timelock::Absolute(lock) => lock.to_cltv_script(),
+        }
+    }
+}
+
+impl From<RelativeLock> for Timelock {
+    fn from(lock: RelativeLock) -> Timelock {
+        Timelock::Relative(lock)
+    }
+}
+
+impl From<AbsoluteLock> for Timelock {
+    fn from(lock: AbsoluteLock) -> Timelock {
+        Timelock::Absolute(lock)
+    }
+}
@@ -25,6 +25,11 @@ pub trait Sign {
     ) -> Result<[u8; 64], SecpError>;
 }
 
+// Verifies a signature against the signer key carried by the signed item itself.
+pub trait Verify {
+    fn verify(&self, signature: [u8; 64], prev_state_hash: [u8; 32]) -> Result<(), SecpError>;
+}
+
 pub trait IntoPoint {
     fn into_point(&self) -> Result<Point, SecpError>;
 }
@@ -31,8 +31,13 @@ pub enum Branch {
 }
 
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TapLeaf {
     leaf_version: u8,
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serialization::serde_hex::bytes")
+    )]
     tap_script: Bytes,
 }
 
@@ -122,6 +127,11 @@ impl TapBranch {
 }
 
 #[derive(Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "TapRootRepr", try_from = "TapRootRepr")
+)]
 pub struct TapRoot {
     inner_key: XOnlyPublicKey,
     tree: Option<TapTree>,
@@ -232,6 +242,45 @@ impl TapRoot {
     }
 }
 
+// The tree is rebuilt from its leaves on deserialization.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct TapRootRepr {
+    #[serde(with = "crate::serialization::serde_hex::key")]
+    inner_key: XOnlyPublicKey,
+    leaves: Option<Vec<TapLeaf>>,
+}
+
+#[cfg(feature = "serde")]
+impl From<TapRoot> for TapRootRepr {
+    fn from(tap_root: TapRoot) -> TapRootRepr {
+        TapRootRepr {
+            inner_key: tap_root.inner_key,
+            leaves: tap_root.tree.map(|tree| tree.leaves),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<TapRootRepr> for TapRoot {
+    type Error = String;
+
+    fn try_from(repr: TapRootRepr) -> Result<TapRoot, String> {
+        let tree = match repr.leaves {
+            Some(leaves) if leaves.is_empty() => {
+                return Err("TapTree must have at least one TapLeaf.".to_string())
+            }
+            Some(leaves) => Some(TapTree::new(leaves)),
+            None => None,
+        };
+
+        Ok(TapRoot {
+            inner_key: repr.inner_key,
+            tree,
+        })
+    }
+}
+
 #[derive(Clone)]
 pub struct TapTree {
     leaves: Vec<TapLeaf>,
@@ -388,10 +437,23 @@ pub fn tree_builder(leaves: &Vec<TapLeaf>, index: Option<usize>) -> (Branch, Opt
     }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ControlBlock {
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serialization::serde_hex::key")
+    )]
     inner_key: XOnlyPublicKey,
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serialization::serde_hex::parity")
+    )]
     parity: Parity,
     leaf_version: u8,
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serialization::serde_hex::bytes")
+    )]
     path: Bytes,
 }
 
@@ -0,0 +1,3 @@
+pub mod pool;
+pub mod sighash;
+pub mod transaction;
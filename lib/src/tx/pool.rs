@@ -0,0 +1,200 @@
+#![allow(dead_code)]
+
+use super::transaction::{Transaction, TxIn, TxOut, SEQUENCE_FINAL};
+use crate::{
+    covenant::{connector_projector_value, CONNECTOR_VALUE},
+    taproot::{TapRoot, P2TR},
+    txo::{connector::Connector, lift::Lift, payload::Payload, projector::Projector},
+    valtype::outpoint::Outpoint,
+};
+use musig2::secp256k1::{self, Parity, XOnlyPublicKey};
+
+type Key = XOnlyPublicKey;
+
+// Smallest P2TR output value that is relayed.
+pub const P2TR_DUST_LIMIT: u64 = 330;
+
+const POOL_TX_VERSION: u32 = 2;
+
+#[derive(Debug, PartialEq)]
+pub enum PoolTransactionError {
+    NoInputs,
+    ValueOverflow,
+    InsufficientFunds { inputs: u64, outputs: u64 },
+    KeyError(secp256k1::Error),
+}
+
+// Unsigned pool transaction template.
+//
+// Inputs:  #0 previous payload (if any), then lifts, then previous operator change outputs.
+// Outputs: #0 payload, #1 VTXO projector, #2 connector projector, #3.. lifts, then bare connectors,
+//          then operator change (if above dust).
+// The connector projector carries exactly what its covenant fans out into connectors.
+pub struct PoolTransaction {
+    payload: Payload,
+    vtxo_projector: Projector,
+    vtxo_projector_value: u64,
+    connector_projector: Projector,
+    num_connectors: usize,
+    operator_key: Key,
+    prev_payload: Option<(Outpoint, u64)>,
+    lifts: Vec<(Outpoint, u64)>,
+    changes: Vec<(Outpoint, u64)>,
+    lift_outputs: Vec<(Lift, u64)>,
+    connector_outputs: Vec<Connector>,
+    payload_value: u64,
+    fee: u64,
+}
+
+impl PoolTransaction {
+    pub fn new(
+        payload: Payload,
+        vtxo_projector: Projector,
+        vtxo_projector_value: u64,
+        connector_projector: Projector,
+        num_connectors: usize,
+        operator_key: Key,
+    ) -> PoolTransaction {
+        PoolTransaction {
+            payload,
+            vtxo_projector,
+            vtxo_projector_value,
+            connector_projector,
+            num_connectors,
+            operator_key,
+            prev_payload: None,
+            lifts: Vec::new(),
+            changes: Vec::new(),
+            lift_outputs: Vec::new(),
+            connector_outputs: Vec::new(),
+            payload_value: P2TR_DUST_LIMIT,
+            fee: 0,
+        }
+    }
+
+    // Spend the payload of the previous pool transaction.
+    pub fn spend_payload(mut self, outpoint: Outpoint, value: u64) -> PoolTransaction {
+        self.prev_payload = Some((outpoint, value));
+        self
+    }
+
+    pub fn spend_lift(mut self, outpoint: Outpoint, value: u64) -> PoolTransaction {
+        self.lifts.push((outpoint, value));
+        self
+    }
+
+    // Spend an operator change output of a previous pool transaction.
+    pub fn spend_change(mut self, outpoint: Outpoint, value: u64) -> PoolTransaction {
+        self.changes.push((outpoint, value));
+        self
+    }
+
+    // Fund a lift output, placed after the projectors.
+    pub fn add_lift_output(mut self, lift: Lift, value: u64) -> PoolTransaction {
+        self.lift_outputs.push((lift, value));
+        self
+    }
+
+    // Fund a bare connector output, placed after the lift outputs.
+    pub fn add_connector_output(mut self, connector: Connector) -> PoolTransaction {
+        self.connector_outputs.push(connector);
+        self
+    }
+
+    // Payload output value, dust by default.
+    pub fn payload_value(mut self, value: u64) -> PoolTransaction {
+        self.payload_value = value;
+        self
+    }
+
+    // Absolute fee left to miners.
+    pub fn fee(mut self, fee: u64) -> PoolTransaction {
+        self.fee = fee;
+        self
+    }
+
+    // Value of the connector projector output: one connector value per projected connector.
+    pub fn connector_projector_value(&self) -> Result<u64, PoolTransactionError> {
+        connector_projector_value(self.num_connectors)
+            .map_err(|_| PoolTransactionError::ValueOverflow)
+    }
+
+    // Operator change: what is left of the inputs after the outputs and the fee.
+    pub fn change(&self) -> Result<u64, PoolTransactionError> {
+        let inputs = sum(self.prevouts().map(|(_, value)| *value))?;
+        let outputs = sum([
+            self.payload_value,
+            self.vtxo_projector_value,
+            self.connector_projector_value()?,
+            self.fee,
+        ]
+        .into_iter()
+        .chain(self.lift_outputs.iter().map(|(_, value)| *value))
+        .chain(self.connector_outputs.iter().map(|_| CONNECTOR_VALUE)))?;
+
+        inputs
+            .checked_sub(outputs)
+            .ok_or(PoolTransactionError::InsufficientFunds { inputs, outputs })
+    }
+
+    pub fn build(&self) -> Result<Transaction, PoolTransactionError> {
+        if self.prevouts().next().is_none() {
+            return Err(PoolTransactionError::NoInputs);
+        }
+
+        let change = self.change()?;
+
+        let inputs = self
+            .prevouts()
+            .map(|(outpoint, _)| TxIn::new(*outpoint, SEQUENCE_FINAL))
+            .collect();
+
+        let mut outputs = vec![
+            TxOut::new(self.payload_value, spk(&self.payload)?),
+            TxOut::new(self.vtxo_projector_value, spk(&self.vtxo_projector)?),
+            TxOut::new(
+                self.connector_projector_value()?,
+                spk(&self.connector_projector)?,
+            ),
+        ];
+
+        for (lift, value) in self.lift_outputs.iter() {
+            outputs.push(TxOut::new(*value, spk(lift)?));
+        }
+
+        for connector in self.connector_outputs.iter() {
+            outputs.push(TxOut::new(CONNECTOR_VALUE, spk(connector)?));
+        }
+
+        // Change below dust goes to the fee.
+        if change >= P2TR_DUST_LIMIT {
+            let operator = TapRoot::key_path_only(self.operator_key.public_key(Parity::Even));
+            let operator_spk = operator.spk().map_err(PoolTransactionError::KeyError)?;
+            outputs.push(TxOut::new(change, operator_spk));
+        }
+
+        Ok(Transaction::new(POOL_TX_VERSION, inputs, outputs, 0))
+    }
+
+    pub fn txid(&self) -> Result<[u8; 32], PoolTransactionError> {
+        Ok(self.build()?.txid())
+    }
+
+    fn prevouts(&self) -> impl Iterator<Item = &(Outpoint, u64)> {
+        self.prev_payload
+            .iter()
+            .chain(self.lifts.iter())
+            .chain(self.changes.iter())
+    }
+}
+
+fn sum(values: impl IntoIterator<Item = u64>) -> Result<u64, PoolTransactionError> {
+    values
+        .into_iter()
+        .try_fold(0u64, |total, value| total.checked_add(value))
+        .ok_or(PoolTransactionError::ValueOverflow)
+}
+
+fn spk(txo: &impl P2TR) -> Result<Vec<u8>, PoolTransactionError> {
+    txo.spk().map_err(PoolTransactionError::KeyError)
+}
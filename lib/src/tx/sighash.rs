@@ -0,0 +1,205 @@
+#![allow(dead_code)]
+
+use super::transaction::{Transaction, TxOut};
+use crate::{
+    hash::{sha_256, tagged_hash, HashTag},
+    serialization::{prefix::Prefix, serialize::Serialize},
+};
+
+type Bytes = Vec<u8>;
+
+const SIGHASH_ANYONECANPAY: u8 = 0x80;
+
+// Script path spends do not track OP_CODESEPARATOR positions.
+const CODESEP_POS_NONE: u32 = 0xffffffff;
+
+#[derive(Debug, PartialEq)]
+pub enum SighashError {
+    InputIndexError,
+    PrevoutsMismatch,
+    SighashTypeError(u8),
+    SingleWithoutOutput,
+    AnnexError,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SighashType {
+    Default,
+    All,
+    None,
+    Single,
+    AllAnyoneCanPay,
+    NoneAnyoneCanPay,
+    SingleAnyoneCanPay,
+}
+
+impl SighashType {
+    pub fn from_byte(byte: u8) -> Result<SighashType, SighashError> {
+        match byte {
+            0x00 => Ok(SighashType::Default),
+            0x01 => Ok(SighashType::All),
+            0x02 => Ok(SighashType::None),
+            0x03 => Ok(SighashType::Single),
+            0x81 => Ok(SighashType::AllAnyoneCanPay),
+            0x82 => Ok(SighashType::NoneAnyoneCanPay),
+            0x83 => Ok(SighashType::SingleAnyoneCanPay),
+            _ => Err(SighashError::SighashTypeError(byte)),
+        }
+    }
+
+    // Byte appended to 64-byte signatures, except for SIGHASH_DEFAULT.
+    pub fn to_byte(&self) -> u8 {
+        match self {
+            SighashType::Default => 0x00,
+            SighashType::All => 0x01,
+            SighashType::None => 0x02,
+            SighashType::Single => 0x03,
+            SighashType::AllAnyoneCanPay => 0x81,
+            SighashType::NoneAnyoneCanPay => 0x82,
+            SighashType::SingleAnyoneCanPay => 0x83,
+        }
+    }
+
+    fn anyone_can_pay(&self) -> bool {
+        self.to_byte() & SIGHASH_ANYONECANPAY != 0
+    }
+
+    fn commits_to_all_outputs(&self) -> bool {
+        matches!(
+            self,
+            SighashType::Default | SighashType::All | SighashType::AllAnyoneCanPay
+        )
+    }
+
+    fn is_single(&self) -> bool {
+        matches!(self, SighashType::Single | SighashType::SingleAnyoneCanPay)
+    }
+}
+
+// BIP-341 signature message of a key path spend with SIGHASH_DEFAULT.
+pub fn key_path_sighash(
+    tx: &Transaction,
+    input_index: usize,
+    prevouts: &[TxOut],
+) -> Result<[u8; 32], SighashError> {
+    tap_sighash(tx, input_index, prevouts, SighashType::Default, None, None)
+}
+
+// BIP-341 signature message.
+// Prevouts are the outputs spent by each input, in input order. With ANYONECANPAY only the one spent
+// by the signed input is committed to, and it may be passed alone.
+// Script path spends pass the tapleaf hash of the executed leaf.
+// https://github.com/bitcoin/bips/blob/master/bip-0341.mediawiki#common-signature-message
+pub fn tap_sighash(
+    tx: &Transaction,
+    input_index: usize,
+    prevouts: &[TxOut],
+    sighash_type: SighashType,
+    leaf_hash: Option<[u8; 32]>,
+    annex: Option<&[u8]>,
+) -> Result<[u8; 32], SighashError> {
+    let inputs = tx.inputs();
+    let outputs = tx.outputs();
+
+    if input_index >= inputs.len() {
+        return Err(SighashError::InputIndexError);
+    }
+
+    // Output spent by the signed input
+    let prevout = match (sighash_type.anyone_can_pay(), prevouts.len()) {
+        (_, len) if len == inputs.len() => &prevouts[input_index],
+        (true, 1) => &prevouts[0],
+        _ => return Err(SighashError::PrevoutsMismatch),
+    };
+
+    // Annexes start with 0x50.
+    if let Some(annex) = annex {
+        if annex.first() != Some(&0x50) {
+            return Err(SighashError::AnnexError);
+        }
+    }
+
+    let mut preimage = Bytes::new();
+
+    // Sighash epoch
+    preimage.push(0x00);
+
+    // Hash type
+    preimage.push(sighash_type.to_byte());
+
+    // Transaction data
+    preimage.extend(tx.version().to_le_bytes());
+    preimage.extend(tx.lock_time().to_le_bytes());
+
+    if !sighash_type.anyone_can_pay() {
+        let mut sha_prevouts = Bytes::new();
+        let mut sha_amounts = Bytes::new();
+        let mut sha_scriptpubkeys = Bytes::new();
+        let mut sha_sequences = Bytes::new();
+
+        for (input, prevout) in inputs.iter().zip(prevouts) {
+            sha_prevouts.extend(input.outpoint().txid());
+            sha_prevouts.extend(input.outpoint().vout().to_le_bytes());
+            sha_amounts.extend(prevout.value().to_le_bytes());
+            sha_scriptpubkeys.extend(prevout.spk().prefix_compact_size());
+            sha_sequences.extend(input.sequence().to_le_bytes());
+        }
+
+        preimage.extend(sha_256(sha_prevouts));
+        preimage.extend(sha_256(sha_amounts));
+        preimage.extend(sha_256(sha_scriptpubkeys));
+        preimage.extend(sha_256(sha_sequences));
+    }
+
+    if sighash_type.commits_to_all_outputs() {
+        let mut sha_outputs = Bytes::new();
+        for output in outputs.iter() {
+            sha_outputs.extend(output.serialize());
+        }
+
+        preimage.extend(sha_256(sha_outputs));
+    }
+
+    // Spend type: ext_flag * 2 + annex_present
+    let ext_flag: u8 = match leaf_hash {
+        Some(_) => 1,
+        None => 0,
+    };
+    preimage.push(ext_flag * 2 + annex.is_some() as u8);
+
+    // Input data
+    match sighash_type.anyone_can_pay() {
+        true => {
+            let input = &inputs[input_index];
+
+            preimage.extend(input.outpoint().txid());
+            preimage.extend(input.outpoint().vout().to_le_bytes());
+            preimage.extend(prevout.value().to_le_bytes());
+            preimage.extend(prevout.spk().prefix_compact_size());
+            preimage.extend(input.sequence().to_le_bytes());
+        }
+        false => preimage.extend((input_index as u32).to_le_bytes()),
+    }
+
+    if let Some(annex) = annex {
+        preimage.extend(sha_256(annex.to_vec().prefix_compact_size()));
+    }
+
+    // Output data
+    if sighash_type.is_single() {
+        let output = outputs
+            .get(input_index)
+            .ok_or(SighashError::SingleWithoutOutput)?;
+
+        preimage.extend(sha_256(output.serialize()));
+    }
+
+    // Script path extension: tapleaf hash, key version, codeseparator position
+    if let Some(leaf_hash) = leaf_hash {
+        preimage.extend(leaf_hash);
+        preimage.push(0x00);
+        preimage.extend(CODESEP_POS_NONE.to_le_bytes());
+    }
+
+    Ok(tagged_hash(preimage, HashTag::TapSighash))
+}
@@ -0,0 +1,319 @@
+#![allow(dead_code)]
+
+use std::io::{Read, Write};
+
+use crate::{
+    hash::hash_256,
+    serialization::{
+        csv::{to_n_sequence_encode, CSVFlag},
+        prefix::{to_compact_size, Prefix},
+        serialize::{write_all, ByteReader, Serialize, SerializeError},
+    },
+    valtype::outpoint::Outpoint,
+};
+
+type Bytes = Vec<u8>;
+
+// Bitcoin's name for a transaction input's previous output reference, serialized as txid || vout.
+pub type OutPoint = Outpoint;
+
+// Final nSequence: no relative timelock, no replacement signaling.
+pub const SEQUENCE_FINAL: u32 = 0xffffffff;
+
+// Segwit serialization marker and flag.
+// https://github.com/bitcoin/bips/blob/master/bip-0144.mediawiki
+const SEGWIT_MARKER: u8 = 0x00;
+const SEGWIT_FLAG: u8 = 0x01;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TxIn {
+    outpoint: Outpoint,
+    script_sig: Bytes,
+    sequence: u32,
+    witness: Vec<Bytes>,
+}
+
+impl TxIn {
+    // Taproot inputs carry an empty scriptSig.
+    pub fn new(outpoint: Outpoint, sequence: u32) -> TxIn {
+        TxIn {
+            outpoint,
+            script_sig: Vec::new(),
+            sequence,
+            witness: Vec::new(),
+        }
+    }
+
+    // Input spending a path locked with OP_CHECKSEQUENCEVERIFY.
+    pub fn new_csv(outpoint: Outpoint, flag: CSVFlag) -> TxIn {
+        let mut sequence = [0x00u8; 4];
+        sequence.copy_from_slice(&to_n_sequence_encode(flag));
+
+        TxIn::new(outpoint, u32::from_le_bytes(sequence))
+    }
+
+    pub fn outpoint(&self) -> Outpoint {
+        self.outpoint
+    }
+
+    pub fn script_sig(&self) -> Bytes {
+        self.script_sig.clone()
+    }
+
+    pub fn sequence(&self) -> u32 {
+        self.sequence
+    }
+
+    pub fn witness(&self) -> Vec<Bytes> {
+        self.witness.clone()
+    }
+
+    pub fn set_witness(&mut self, witness: Vec<Bytes>) {
+        self.witness = witness;
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TxOut {
+    value: u64,
+    spk: Bytes,
+}
+
+impl TxOut {
+    pub fn new(value: u64, spk: Bytes) -> TxOut {
+        TxOut { value, spk }
+    }
+
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    pub fn spk(&self) -> Bytes {
+        self.spk.clone()
+    }
+}
+
+// Bitcoin transaction, serialized in the segwit format when any of its inputs carries a witness.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transaction {
+    version: u32,
+    inputs: Vec<TxIn>,
+    outputs: Vec<TxOut>,
+    lock_time: u32,
+}
+
+impl Transaction {
+    pub fn new(
+        version: u32,
+        inputs: Vec<TxIn>,
+        outputs: Vec<TxOut>,
+        lock_time: u32,
+    ) -> Transaction {
+        Transaction {
+            version,
+            inputs,
+            outputs,
+            lock_time,
+        }
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn inputs(&self) -> Vec<TxIn> {
+        self.inputs.clone()
+    }
+
+    pub fn outputs(&self) -> Vec<TxOut> {
+        self.outputs.clone()
+    }
+
+    pub fn lock_time(&self) -> u32 {
+        self.lock_time
+    }
+
+    pub fn set_witness(&mut self, input_index: usize, witness: Vec<Bytes>) -> Option<()> {
+        self.inputs.get_mut(input_index)?.set_witness(witness);
+        Some(())
+    }
+
+    pub fn has_witness(&self) -> bool {
+        self.inputs.iter().any(|input| !input.witness.is_empty())
+    }
+
+    // Serialization without witnesses.
+    pub fn serialize_without_witness(&self) -> Bytes {
+        let mut bytes = Vec::<u8>::new();
+        self.write_with(&mut bytes, false)
+            .expect("Writing into a byte vector cannot fail.");
+        bytes
+    }
+
+    // Txid in internal byte order, as referenced by outpoints.
+    // Displayed txids are byte-reversed.
+    pub fn txid(&self) -> [u8; 32] {
+        hash_256(self.serialize_without_witness())
+    }
+
+    // Same as the txid for transactions without witnesses.
+    pub fn wtxid(&self) -> [u8; 32] {
+        hash_256(self.serialize())
+    }
+
+    fn write_with<W: Write>(&self, writer: &mut W, witness: bool) -> Result<(), SerializeError> {
+        // Version
+        write_all(writer, &self.version.to_le_bytes())?;
+
+        // Marker and flag
+        if witness {
+            write_all(writer, &[SEGWIT_MARKER, SEGWIT_FLAG])?;
+        }
+
+        // Inputs
+        write_all(writer, &to_compact_size(self.inputs.len() as u64))?;
+        for input in self.inputs.iter() {
+            input.write_to(writer)?;
+        }
+
+        // Outputs
+        write_all(writer, &to_compact_size(self.outputs.len() as u64))?;
+        for output in self.outputs.iter() {
+            output.write_to(writer)?;
+        }
+
+        // Witnesses
+        if witness {
+            for input in self.inputs.iter() {
+                write_all(writer, &to_compact_size(input.witness.len() as u64))?;
+                for item in input.witness.iter() {
+                    write_all(writer, &item.prefix_compact_size())?;
+                }
+            }
+        }
+
+        // Lock time
+        write_all(writer, &self.lock_time.to_le_bytes())
+    }
+}
+
+impl Serialize for TxIn {
+    fn write_to<W: Write>(&self, writer: &mut W) -> Result<(), SerializeError> {
+        // Outpoint
+        self.outpoint.write_to(writer)?;
+
+        // ScriptSig
+        write_all(writer, &self.script_sig.prefix_compact_size())?;
+
+        // Sequence
+        write_all(writer, &self.sequence.to_le_bytes())
+    }
+
+    fn read_from<R: Read>(reader: &mut ByteReader<R>) -> Result<TxIn, SerializeError> {
+        // Outpoint
+        let outpoint = Outpoint::read_from(reader)?;
+
+        // ScriptSig
+        let script_sig = reader.read_compact_size_prefixed()?;
+
+        // Sequence
+        let sequence = reader.read_u32_le()?;
+
+        Ok(TxIn {
+            outpoint,
+            script_sig,
+            sequence,
+            witness: Vec::new(),
+        })
+    }
+}
+
+impl Serialize for TxOut {
+    fn write_to<W: Write>(&self, writer: &mut W) -> Result<(), SerializeError> {
+        // Value
+        write_all(writer, &self.value.to_le_bytes())?;
+
+        // ScriptPubKey
+        write_all(writer, &self.spk.prefix_compact_size())
+    }
+
+    fn read_from<R: Read>(reader: &mut ByteReader<R>) -> Result<TxOut, SerializeError> {
+        // Value
+        let value = reader.read_u64_le()?;
+
+        // ScriptPubKey
+        let spk = reader.read_compact_size_prefixed()?;
+
+        Ok(TxOut { value, spk })
+    }
+}
+
+impl Serialize for Transaction {
+    fn write_to<W: Write>(&self, writer: &mut W) -> Result<(), SerializeError> {
+        self.write_with(writer, self.has_witness())
+    }
+
+    fn read_from<R: Read>(reader: &mut ByteReader<R>) -> Result<Transaction, SerializeError> {
+        // Version
+        let version = reader.read_u32_le()?;
+
+        // Marker and flag
+        let marker_offset = reader.offset();
+        let segwit = reader.peek_u8()? == SEGWIT_MARKER;
+        if segwit {
+            reader.read_u8()?;
+
+            let flag_offset = reader.offset();
+            let flag = reader.read_u8()?;
+            if flag != SEGWIT_FLAG {
+                return Err(SerializeError::InvalidFlag {
+                    offset: flag_offset,
+                    flag,
+                });
+            }
+        }
+
+        // Inputs
+        let input_count = reader.read_compact_size()?;
+        let mut inputs = Vec::<TxIn>::new();
+        for _ in 0..input_count {
+            inputs.push(TxIn::read_from(reader)?);
+        }
+
+        // Outputs
+        let output_count = reader.read_compact_size()?;
+        let mut outputs = Vec::<TxOut>::new();
+        for _ in 0..output_count {
+            outputs.push(TxOut::read_from(reader)?);
+        }
+
+        // Witnesses
+        if segwit {
+            for input in inputs.iter_mut() {
+                let item_count = reader.read_compact_size()?;
+                for _ in 0..item_count {
+                    input.witness.push(reader.read_compact_size_prefixed()?);
+                }
+            }
+        }
+
+        // Lock time
+        let lock_time = reader.read_u32_le()?;
+
+        let tx = Transaction {
+            version,
+            inputs,
+            outputs,
+            lock_time,
+        };
+
+        // The segwit format is only used when there are witnesses.
+        if segwit && !tx.has_witness() {
+            return Err(SerializeError::NonCanonicalEncoding {
+                offset: marker_offset,
+            });
+        }
+
+        Ok(tx)
+    }
+}
@@ -1,7 +1,11 @@
 #![allow(dead_code)]
 
 use crate::{
-    serialization::csv::{to_csv_script_encode, CSVFlag},
+    script::{
+        builder::ScriptBuilder,
+        opcode::{OP_CHECKSIG, OP_CHECKSIGVERIFY},
+    },
+    serialization::csv::CSVFlag,
     taproot::{TapLeaf, TapRoot, P2TR},
 };
 use musig2::secp256k1::{self, XOnlyPublicKey};
@@ -38,25 +42,16 @@ fn taproot(&self) -> Result<TapRoot, secp256k1::Error> {
     let mut leaves = Vec::<TapLeaf>::new();
 
     for i in 0..128 {
-        let mut tap_script = Vec::<u8>::new();
-
         // Add degrading timelock
         let days: u8 = DEGRADING_PERIOD_START_AT - i;
-        tap_script.extend(to_csv_script_encode(CSVFlag::Days(days)));
-
-        // Push to_self key
-        tap_script.push(0x20);
-        tap_script.extend(self.to_self_key().serialize());
-
-        // OP_CHECKSIGVERIFY
-        tap_script.push(0xad);
-
-        // Push to_operator key
-        tap_script.push(0x20);
-        tap_script.extend(self.to_operator_key().serialize());
 
-        // OP_CHECKSIG
-        tap_script.push(0xac);
+        let tap_script = ScriptBuilder::new()
+            .push_csv(CSVFlag::Days(days))
+            .push_key(&self.to_self_key())
+            .push_opcode(OP_CHECKSIGVERIFY)
+            .push_key(&self.to_operator_key())
+            .push_opcode(OP_CHECKSIG)
+            .into_script();
 
         leaves.push(TapLeaf::new(tap_script));
     }
@@ -1,8 +1,9 @@
 #![allow(dead_code)]
 
 use crate::{
+    script::{builder::ScriptBuilder, opcode::OP_CHECKSIG},
+    serialization::{csv::RelativeLock, timelock::Timelock},
     signature::musig2::keys_to_key_agg_ctx,
-    serialization::csv::{to_csv_script_encode, CSVFlag},
     taproot::{TapLeaf, TapRoot, P2TR},
     well_known::operator,
 };
@@ -14,9 +15,13 @@ use musig2::{
 type Bytes = Vec<u8>;
 type Key = XOnlyPublicKey;
 
+// Default exit path timelock: 12 months
+const LIFT_EXIT_LOCK: RelativeLock = RelativeLock::Blocks(52560);
+
 pub struct Lift {
     self_key: Key,
     operator_key_well_known: Key,
+    exit_timelock: Timelock,
 }
 
 impl Lift {
@@ -25,6 +30,7 @@ impl Lift {
         Lift {
             self_key,
             operator_key_well_known,
+            exit_timelock: Timelock::Relative(LIFT_EXIT_LOCK),
         }
     }
 
@@ -32,6 +38,7 @@ impl Lift {
         Lift {
             self_key,
             operator_key_well_known,
+            exit_timelock: Timelock::Relative(LIFT_EXIT_LOCK),
         }
     }
 
@@ -43,6 +50,14 @@ impl Lift {
         self.operator_key_well_known
     }
 
+    pub fn exit_timelock(&self) -> Timelock {
+        self.exit_timelock
+    }
+
+    pub fn set_exit_timelock(&mut self, timelock: Timelock) {
+        self.exit_timelock = timelock;
+    }
+
     pub fn key_agg_ctx(&self) -> Result<KeyAggContext, secp256k1::Error> {
         let keys = vec![self.self_key(), self.operator_key()];
         keys_to_key_agg_ctx(&keys).map_err(|_| secp256k1::Error::InvalidPublicKey)
@@ -56,11 +71,11 @@ impl P2TR for Lift {
         let inner_key: PublicKey = key_agg_ctx.aggregated_pubkey();
 
         //// Exit Path: (Self after 3 months)
-        let mut exit_path_script = Vec::<u8>::new();
-        exit_path_script.extend(to_csv_script_encode(CSVFlag::CSVYear)); // Relative Timelock
-        exit_path_script.push(0x20); // OP_PUSHDATA_32
-        exit_path_script.extend(self.self_key().serialize()); // Self Key 32-bytes
-        exit_path_script.push(0xac); // OP_CHECKSIG
+        let exit_path_script = ScriptBuilder::new()
+            .push_timelock(self.exit_timelock()) // Relative or Absolute Timelock
+            .push_key(&self.self_key()) // Self Key 32-bytes
+            .push_opcode(OP_CHECKSIG)
+            .into_script();
         let exit_path = TapLeaf::new(exit_path_script);
 
         Ok(TapRoot::key_and_script_path_single(inner_key, exit_path))
@@ -69,4 +84,4 @@ impl P2TR for Lift {
     fn spk(&self) -> Result<Bytes, secp256k1::Error> {
         self.taproot()?.spk()
     }
-}
\ No newline at end of file
+}
@@ -3,4 +3,7 @@ pub mod connector;
 pub mod lift;
 pub mod payload;
 pub mod projector;
+pub mod remote;
+pub mod self_txo;
+pub mod trigger;
 pub mod vtxo;
@@ -1,19 +1,101 @@
 #![allow(dead_code)]
 
-use bit_vec::BitVec;
 use musig2::secp256k1::{self, XOnlyPublicKey};
 
+use crate::entry::compressor::{compress_entries, CommonTables};
 use crate::entry::entry::Entry;
-use crate::signature::musig2::keys_to_key_agg_ctx;
-use crate::serialization::cpe::CompactPayloadEncoding;
+use crate::script::builder::ScriptBuilder;
+use crate::script::opcode::{
+    OP_CHECKSIG, OP_CSV, OP_DROP, OP_ELSE, OP_ENDIF, OP_EQUALVERIFY, OP_HASH160, OP_IF,
+};
+use crate::serialization::bits::{BitReader, BitWriter};
+use crate::serialization::cpe::{
+    CPEContext, CPEDecodingError, CompactPayloadDecoding, CompactPayloadEncoding,
+};
 use crate::serialization::csv::CSVFlag;
-use crate::serialization::push::Push;
+use crate::serialization::prefix::read_pushdata;
+use crate::serialization::push::{from_multi_pushdata_push, Push};
+use crate::signature::musig2::keys_to_key_agg_ctx;
 use crate::taproot::{TapLeaf, P2TR};
-use crate::{hash::hash_160, serialization::csv::to_csv_script_encode, taproot::TapRoot};
+use crate::{hash::hash_160, taproot::TapRoot};
 
 type Bytes = Vec<u8>;
 type Key = XOnlyPublicKey;
 
+// Version of the payload data layout.
+pub const PAYLOAD_VERSION: u8 = 0x04;
+
+// Version (1) + fee rate (1) + basis points (1) + fresh operator key (32) + projector signatures (64 + 64) + padding count (1)
+const PAYLOAD_HEADER_LEN: usize = 164;
+
+// https://github.com/bitcoin/bitcoin/blob/master/src/script/script.h#L27
+const MAX_PUSH_LEN: usize = 520;
+
+#[derive(Debug, PartialEq)]
+pub enum PayloadError {
+    TapscriptParseError,
+    WitnessParseError,
+    HeaderLengthError,
+    VersionError(u8),
+    KeyParseError,
+    EntryDecodingError(CPEDecodingError),
+}
+
+pub struct DecodedPayload {
+    operator_key_well_known: Key,
+    hashlocks: Vec<[u8; 20]>,
+    msg_senders_aggregate_key: Key,
+    sats_per_vbyte: u8,
+    liquidity_basis_points: u8,
+    fresh_operator_key_dynamic: Key,
+    vtxo_projector_agg_sig: [u8; 64],
+    connector_projector_agg_sig: [u8; 64],
+    common_tables: CommonTables,
+    entries: Vec<Entry>,
+}
+
+impl DecodedPayload {
+    pub fn operator_key(&self) -> Key {
+        self.operator_key_well_known
+    }
+
+    pub fn hashlocks(&self) -> Vec<[u8; 20]> {
+        self.hashlocks.clone()
+    }
+
+    pub fn msg_senders_aggregate_key(&self) -> Key {
+        self.msg_senders_aggregate_key
+    }
+
+    pub fn sats_per_vbyte(&self) -> u8 {
+        self.sats_per_vbyte
+    }
+
+    pub fn liquidity_basis_points(&self) -> u8 {
+        self.liquidity_basis_points
+    }
+
+    pub fn fresh_operator_key(&self) -> Key {
+        self.fresh_operator_key_dynamic
+    }
+
+    pub fn vtxo_projector_agg_sig(&self) -> [u8; 64] {
+        self.vtxo_projector_agg_sig
+    }
+
+    pub fn connector_projector_agg_sig(&self) -> [u8; 64] {
+        self.connector_projector_agg_sig
+    }
+
+    pub fn common_tables(&self) -> CommonTables {
+        self.common_tables.clone()
+    }
+
+    pub fn entries(&self) -> Vec<Entry> {
+        self.entries.clone()
+    }
+}
+
 pub struct Payload {
     msg_senders: Vec<Key>,
     operator_key_well_known: Key,
@@ -23,6 +105,7 @@ pub struct Payload {
     fresh_operator_key_dynamic: Key,
     vtxo_projector_agg_sig: [u8; 64],
     connector_projector_agg_sig: [u8; 64],
+    common_tables: CommonTables,
     entries: Vec<Entry>,
 }
 
@@ -47,10 +130,20 @@ impl Payload {
             fresh_operator_key_dynamic,
             vtxo_projector_agg_sig,
             connector_projector_agg_sig,
+            common_tables: CommonTables::default(),
             entries,
         }
     }
 
+    // Move the most repeated recipients and amounts into the common tables,
+    // and have the entries reference them by common index.
+    pub fn compress(&mut self) {
+        let compressed = compress_entries(&self.entries);
+
+        self.common_tables = compressed.common_tables();
+        self.entries = compressed.entries();
+    }
+
     fn group_s_commitments_by_two(&self) -> Vec<([u8; 32], Option<[u8; 32]>)> {
         let s_commitments = self.s_commitments.clone();
         let mut tuples: Vec<([u8; 32], Option<[u8; 32]>)> = Vec::new();
@@ -97,7 +190,10 @@ impl Payload {
     fn payload(&self) -> Bytes {
         let mut data = Vec::<u8>::new();
 
-        // Start with feerate
+        // Start with the payload version
+        data.push(PAYLOAD_VERSION);
+
+        // Add feerate
         data.push(self.sats_per_vbyte);
 
         // Add basis points
@@ -112,16 +208,17 @@ impl Payload {
         // Add connector_projector_agg_sig (64 bytes)
         data.extend(self.connector_projector_agg_sig);
 
-        let mut entries_whole = BitVec::new();
+        let mut entries_whole = BitWriter::new();
+
+        // Common tables go ahead of the entries referencing them.
+        self.common_tables.write_cpe(&mut entries_whole);
 
         for entry in self.entries.iter() {
-            entries_whole.extend(entry.to_cpe());
+            entry.write_cpe(&mut entries_whole);
         }
 
-        let zero_bits_padded: u8 = 8 - (entries_whole.len() % 8) as u8;
-
-        // Add the length of padded zero-bits
-        data.push(zero_bits_padded);
+        // Add the number of zero-bits padded to the byte boundary (0..=7)
+        data.push(entries_whole.padding_len());
 
         // Add entries
         data.extend(entries_whole.to_bytes());
@@ -129,59 +226,165 @@ impl Payload {
         data
     }
 
-    fn msg_senders_aggregate_key(&self) -> XOnlyPublicKey {
-        let key_agg_ctx = keys_to_key_agg_ctx(&self.msg_senders);
+    // Size of the payload data pushes for entries of the given CPE bit length.
+    // The entries follow empty common tables.
+    // The data is split into 520-byte chunks, each prefixed with its OP_PUSHDATA opcode.
+    // A trailing 1-byte chunk may be pushed as a single opcode, one byte less than counted here.
+    pub fn data_push_len(entries_bits: usize) -> usize {
+        let table_bits = CommonTables::default().to_cpe().len();
+        let data_len = PAYLOAD_HEADER_LEN + (table_bits + entries_bits).div_ceil(8);
 
-        // consider removing unwrap here
-        key_agg_ctx.unwrap().aggregated_pubkey()
+        let chunk_push_len = |chunk_len: usize| match chunk_len {
+            0 => 0,
+            1..=75 => 1 + chunk_len,
+            76..=255 => 2 + chunk_len,
+            _ => 3 + chunk_len,
+        };
+
+        (data_len / MAX_PUSH_LEN) * chunk_push_len(MAX_PUSH_LEN)
+            + chunk_push_len(data_len % MAX_PUSH_LEN)
     }
-}
 
-impl P2TR for Payload {
-    fn taproot(&self) -> Result<TapRoot, secp256k1::Error>  {
-        let mut tap_script = Vec::<u8>::new();
+    // Parse a payload tapscript back into its hashlocks, keys, header fields and entries.
+    pub fn from_tapscript(
+        tap_script: &[u8],
+        context: &impl CPEContext,
+    ) -> Result<DecodedPayload, PayloadError> {
+        let mut cursor: usize = 0;
 
-        // OP_IF
-        tap_script.push(0x63);
+        expect_byte(tap_script, &mut cursor, OP_IF)?;
 
-        // Haslocks
-        let hashlocks = self.hashlocks();
-        for hashlock in hashlocks {
-            // OP_HASH160
-            tap_script.push(0xa9);
+        // Hashlocks
+        let mut hashlocks = Vec::<[u8; 20]>::new();
+        while tap_script.get(cursor) == Some(&OP_HASH160) {
+            cursor += 1;
 
             // Push hash into stack
-            tap_script.push(0x14);
-            tap_script.extend(hashlock);
+            expect_byte(tap_script, &mut cursor, 0x14)?;
+            let mut hashlock = [0x00u8; 20];
+            hashlock.copy_from_slice(take(tap_script, &mut cursor, 20)?);
+            hashlocks.push(hashlock);
+
+            expect_byte(tap_script, &mut cursor, OP_EQUALVERIFY)?;
+        }
+
+        // Operator key
+        let operator_key_well_known = read_key_push(tap_script, &mut cursor)?;
+        expect_byte(tap_script, &mut cursor, OP_CHECKSIG)?;
+
+        expect_byte(tap_script, &mut cursor, OP_ELSE)?;
+
+        // Relative timelock
+        read_push(tap_script, &mut cursor)?;
+        expect_byte(tap_script, &mut cursor, OP_CSV)?;
+        expect_byte(tap_script, &mut cursor, OP_DROP)?;
+
+        // msg.senders aggregate key
+        let msg_senders_aggregate_key = read_key_push(tap_script, &mut cursor)?;
+        expect_byte(tap_script, &mut cursor, OP_CHECKSIG)?;
+
+        expect_byte(tap_script, &mut cursor, OP_ENDIF)?;
+
+        // Rejoin the payload pushes
+        let data = from_multi_pushdata_push(&tap_script[cursor..])
+            .map_err(|_| PayloadError::TapscriptParseError)?;
 
-            // OP_EQUALVERIFY
-            tap_script.push(0x88);
+        if data.len() < PAYLOAD_HEADER_LEN {
+            return Err(PayloadError::HeaderLengthError);
         }
 
-        // Push operator key into stack
-        tap_script.push(0x20);
-        tap_script.extend(self.operator_key_well_known.serialize());
+        let version = data[0];
+        if version != PAYLOAD_VERSION {
+            return Err(PayloadError::VersionError(version));
+        }
+
+        let sats_per_vbyte = data[1];
+        let liquidity_basis_points = data[2];
+        let fresh_operator_key_dynamic =
+            Key::from_slice(&data[3..35]).map_err(|_| PayloadError::KeyParseError)?;
+
+        let mut vtxo_projector_agg_sig = [0x00u8; 64];
+        vtxo_projector_agg_sig.copy_from_slice(&data[35..99]);
+
+        let mut connector_projector_agg_sig = [0x00u8; 64];
+        connector_projector_agg_sig.copy_from_slice(&data[99..163]);
+
+        let zero_bits_padded = data[163];
+        let mut reader = BitReader::from_padded(&data[PAYLOAD_HEADER_LEN..], zero_bits_padded)
+            .map_err(PayloadError::EntryDecodingError)?;
+
+        // Common tables
+        let common_tables = CommonTables::from_cpe(&mut reader, context)
+            .map_err(PayloadError::EntryDecodingError)?;
+
+        // Entries, with common references resolved from the tables
+        let entries = read_entries(&mut reader, &common_tables.with_context(context))?;
 
-        // OP_CHECKSIG
-        tap_script.push(0xac);
+        Ok(DecodedPayload {
+            operator_key_well_known,
+            hashlocks,
+            msg_senders_aggregate_key,
+            sats_per_vbyte,
+            liquidity_basis_points,
+            fresh_operator_key_dynamic,
+            vtxo_projector_agg_sig,
+            connector_projector_agg_sig,
+            common_tables,
+            entries,
+        })
+    }
+
+    // Parse a payload from the full script-path spending witness: [..stack, tapscript, control block, (annex)].
+    pub fn from_witness(
+        witness: &[Bytes],
+        context: &impl CPEContext,
+    ) -> Result<DecodedPayload, PayloadError> {
+        let mut witness_len = witness.len();
+
+        // Skip the annex, if present.
+        if let Some(last) = witness.last() {
+            if witness_len >= 2 && last.first() == Some(&0x50) {
+                witness_len -= 1;
+            }
+        }
+
+        if witness_len < 2 {
+            return Err(PayloadError::WitnessParseError);
+        }
 
-        // OP_ELSE
-        tap_script.push(0x67);
+        Payload::from_tapscript(&witness[witness_len - 2], context)
+    }
 
-        tap_script.extend(to_csv_script_encode(CSVFlag::CSVWeek));
+    fn msg_senders_aggregate_key(&self) -> XOnlyPublicKey {
+        let key_agg_ctx = keys_to_key_agg_ctx(&self.msg_senders);
 
-        // Push msg.senders aggregate key into stack
-        tap_script.push(0x20);
-        tap_script.extend(self.msg_senders_aggregate_key().serialize());
+        // consider removing unwrap here
+        key_agg_ctx.unwrap().aggregated_pubkey()
+    }
+}
 
-        // OP_CHECKSIG
-        tap_script.push(0xac);
+impl P2TR for Payload {
+    fn taproot(&self) -> Result<TapRoot, secp256k1::Error> {
+        let mut tap_script = ScriptBuilder::new().push_opcode(OP_IF);
 
-        // OP_ENDIF
-        tap_script.push(0x68);
+        // Haslocks
+        for hashlock in self.hashlocks() {
+            tap_script = tap_script.push_hash160_lock(&hashlock);
+        }
 
-        // Push payload
-        tap_script.extend(self.payload().as_multi_pushdata_push());
+        let tap_script = tap_script
+            // Push operator key into stack
+            .push_key(&self.operator_key_well_known)
+            .push_opcode(OP_CHECKSIG)
+            .push_opcode(OP_ELSE)
+            .push_csv(CSVFlag::CSVWeek)
+            // Push msg.senders aggregate key into stack
+            .push_key(&self.msg_senders_aggregate_key())
+            .push_opcode(OP_CHECKSIG)
+            .push_opcode(OP_ENDIF)
+            // Push payload
+            .push_raw(&self.payload().as_multi_pushdata_push())
+            .into_script();
 
         let tap_leaf = TapLeaf::new(tap_script);
         let tap_root = TapRoot::script_path_only_single(tap_leaf);
@@ -192,4 +395,52 @@ impl P2TR for Payload {
     fn spk(&self) -> Result<Bytes, secp256k1::Error> {
         self.taproot()?.spk()
     }
-}
\ No newline at end of file
+}
+
+fn read_entries(
+    reader: &mut BitReader,
+    context: &impl CPEContext,
+) -> Result<Vec<Entry>, PayloadError> {
+    let mut entries = Vec::<Entry>::new();
+
+    while !reader.is_empty() {
+        let entry = Entry::from_cpe(reader, context).map_err(PayloadError::EntryDecodingError)?;
+        entries.push(entry);
+    }
+
+    Ok(entries)
+}
+
+fn take<'a>(script: &'a [u8], cursor: &mut usize, len: usize) -> Result<&'a [u8], PayloadError> {
+    let end = cursor
+        .checked_add(len)
+        .ok_or(PayloadError::TapscriptParseError)?;
+    let slice = script
+        .get(*cursor..end)
+        .ok_or(PayloadError::TapscriptParseError)?;
+
+    *cursor = end;
+    Ok(slice)
+}
+
+fn expect_byte(script: &[u8], cursor: &mut usize, byte: u8) -> Result<(), PayloadError> {
+    match take(script, cursor, 1)? {
+        [b] if *b == byte => Ok(()),
+        _ => Err(PayloadError::TapscriptParseError),
+    }
+}
+
+fn read_key_push(script: &[u8], cursor: &mut usize) -> Result<Key, PayloadError> {
+    // OP_PUSHBYTES_32
+    expect_byte(script, cursor, 0x20)?;
+    Key::from_slice(take(script, cursor, 32)?).map_err(|_| PayloadError::KeyParseError)
+}
+
+// Read a single push, interpreting minimal pushes as the single byte they stand for.
+fn read_push(script: &[u8], cursor: &mut usize) -> Result<Bytes, PayloadError> {
+    let (data, len) =
+        read_pushdata(&script[*cursor..]).map_err(|_| PayloadError::TapscriptParseError)?;
+
+    *cursor += len;
+    Ok(data)
+}
@@ -1,8 +1,9 @@
 #![allow(dead_code)]
 
 use crate::{
+    script::{builder::ScriptBuilder, opcode::OP_CHECKSIG},
+    serialization::{csv::RelativeLock, timelock::Timelock},
     signature::musig2::keys_to_key_agg_ctx,
-    serialization::csv::{to_csv_script_encode, CSVFlag},
     taproot::{TapLeaf, TapRoot, P2TR},
     well_known::operator,
 };
@@ -14,6 +15,9 @@ use musig2::{
 type Bytes = Vec<u8>;
 type Key = XOnlyPublicKey;
 
+// Default sweep path timelock: 3 months
+const PROJECTOR_SWEEP_LOCK: RelativeLock = RelativeLock::Blocks(12960);
+
 #[derive(Clone, Copy)]
 pub enum ProjectorTag {
     VTXOProjector,
@@ -25,6 +29,7 @@ pub struct Projector {
     msg_sender_keys: Vec<Key>,
     operator_key_well_known: Key,
     tag: ProjectorTag,
+    sweep_timelock: Timelock,
 }
 
 impl Projector {
@@ -35,6 +40,20 @@ impl Projector {
             msg_sender_keys,
             operator_key_well_known,
             tag,
+            sweep_timelock: Timelock::Relative(PROJECTOR_SWEEP_LOCK),
+        }
+    }
+
+    pub fn new_with_operator(
+        msg_sender_keys: Vec<Key>,
+        operator_key_well_known: Key,
+        tag: ProjectorTag,
+    ) -> Projector {
+        Projector {
+            msg_sender_keys,
+            operator_key_well_known,
+            tag,
+            sweep_timelock: Timelock::Relative(PROJECTOR_SWEEP_LOCK),
         }
     }
 
@@ -55,6 +74,14 @@ impl Projector {
     pub fn tag(&self) -> ProjectorTag {
         self.tag
     }
+
+    pub fn sweep_timelock(&self) -> Timelock {
+        self.sweep_timelock
+    }
+
+    pub fn set_sweep_timelock(&mut self, timelock: Timelock) {
+        self.sweep_timelock = timelock;
+    }
 }
 
 impl P2TR for Projector {
@@ -64,11 +91,11 @@ impl P2TR for Projector {
         let inner_key: PublicKey = key_agg_ctx.aggregated_pubkey();
 
         //// Sweep Path: (Operator after 3 months)
-        let mut sweep_path_script = Vec::<u8>::new();
-        sweep_path_script.extend(to_csv_script_encode(CSVFlag::CSVThreeMonths)); // Relative Timelock
-        sweep_path_script.push(0x20); // OP_PUSHDATA_32
-        sweep_path_script.extend(self.operator_key().serialize()); // Operator Key 32-bytes
-        sweep_path_script.push(0xac); // OP_CHECKSIG
+        let sweep_path_script = ScriptBuilder::new()
+            .push_timelock(self.sweep_timelock()) // Relative or Absolute Timelock
+            .push_key(&self.operator_key()) // Operator Key 32-bytes
+            .push_opcode(OP_CHECKSIG)
+            .into_script();
         let sweep_path = TapLeaf::new(sweep_path_script);
 
         Ok(TapRoot::key_and_script_path_single(inner_key, sweep_path))
@@ -77,4 +104,4 @@ impl P2TR for Projector {
     fn spk(&self) -> Result<Bytes, secp256k1::Error> {
         self.taproot()?.spk()
     }
-}
\ No newline at end of file
+}
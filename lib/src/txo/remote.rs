@@ -0,0 +1,34 @@
+#![allow(dead_code)]
+
+use crate::taproot::{TapRoot, P2TR};
+use musig2::secp256k1::{self, Parity, XOnlyPublicKey};
+
+type Bytes = Vec<u8>;
+type Key = XOnlyPublicKey;
+
+pub struct Remote {
+    remote_key: Key,
+}
+
+impl Remote {
+    pub fn new(remote_key: Key) -> Remote {
+        Remote { remote_key }
+    }
+
+    pub fn remote_key(&self) -> Key {
+        self.remote_key
+    }
+}
+
+impl P2TR for Remote {
+    fn taproot(&self) -> Result<TapRoot, secp256k1::Error> {
+        //// Inner Key: (Remote)
+        let inner_key = self.remote_key().public_key(Parity::Even);
+
+        Ok(TapRoot::key_path_only(inner_key))
+    }
+
+    fn spk(&self) -> Result<Bytes, secp256k1::Error> {
+        self.taproot()?.spk()
+    }
+}
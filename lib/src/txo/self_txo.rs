@@ -0,0 +1,34 @@
+#![allow(dead_code)]
+
+use crate::taproot::{TapRoot, P2TR};
+use musig2::secp256k1::{self, Parity, XOnlyPublicKey};
+
+type Bytes = Vec<u8>;
+type Key = XOnlyPublicKey;
+
+pub struct SelfTXO {
+    self_key: Key,
+}
+
+impl SelfTXO {
+    pub fn new(self_key: Key) -> SelfTXO {
+        SelfTXO { self_key }
+    }
+
+    pub fn self_key(&self) -> Key {
+        self.self_key
+    }
+}
+
+impl P2TR for SelfTXO {
+    fn taproot(&self) -> Result<TapRoot, secp256k1::Error> {
+        //// Inner Key: (Self)
+        let inner_key = self.self_key().public_key(Parity::Even);
+
+        Ok(TapRoot::key_path_only(inner_key))
+    }
+
+    fn spk(&self) -> Result<Bytes, secp256k1::Error> {
+        self.taproot()?.spk()
+    }
+}
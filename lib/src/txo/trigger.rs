@@ -0,0 +1,87 @@
+#![allow(dead_code)]
+
+use crate::{
+    script::{builder::ScriptBuilder, opcode::OP_CHECKSIG},
+    serialization::{csv::RelativeLock, timelock::Timelock},
+    signature::musig2::keys_to_key_agg_ctx,
+    taproot::{TapLeaf, TapRoot, P2TR},
+    well_known::operator,
+};
+use musig2::{
+    secp256k1::{self, PublicKey, XOnlyPublicKey},
+    KeyAggContext,
+};
+
+type Bytes = Vec<u8>;
+type Key = XOnlyPublicKey;
+
+// Default exit path timelock: 1 week
+const TRIGGER_EXIT_LOCK: RelativeLock = RelativeLock::Blocks(1008);
+
+pub struct Trigger {
+    self_key: Key,
+    operator_key_well_known: Key,
+    exit_timelock: Timelock,
+}
+
+impl Trigger {
+    pub fn new(self_key: Key) -> Trigger {
+        let operator_key_well_known = Key::from_slice(&operator::OPERATOR_KEY_WELL_KNOWN).unwrap();
+        Trigger {
+            self_key,
+            operator_key_well_known,
+            exit_timelock: Timelock::Relative(TRIGGER_EXIT_LOCK),
+        }
+    }
+
+    pub fn new_with_operator(self_key: Key, operator_key_well_known: Key) -> Trigger {
+        Trigger {
+            self_key,
+            operator_key_well_known,
+            exit_timelock: Timelock::Relative(TRIGGER_EXIT_LOCK),
+        }
+    }
+
+    pub fn self_key(&self) -> Key {
+        self.self_key
+    }
+
+    pub fn operator_key(&self) -> Key {
+        self.operator_key_well_known
+    }
+
+    pub fn exit_timelock(&self) -> Timelock {
+        self.exit_timelock
+    }
+
+    pub fn set_exit_timelock(&mut self, timelock: Timelock) {
+        self.exit_timelock = timelock;
+    }
+
+    pub fn key_agg_ctx(&self) -> Result<KeyAggContext, secp256k1::Error> {
+        let keys = vec![self.self_key(), self.operator_key()];
+        keys_to_key_agg_ctx(&keys).map_err(|_| secp256k1::Error::InvalidPublicKey)
+    }
+}
+
+impl P2TR for Trigger {
+    fn taproot(&self) -> Result<TapRoot, secp256k1::Error> {
+        //// Inner Key: (Self + Operator)
+        let key_agg_ctx = self.key_agg_ctx()?;
+        let inner_key: PublicKey = key_agg_ctx.aggregated_pubkey();
+
+        //// Exit Path: (Self after 1 week)
+        let exit_path_script = ScriptBuilder::new()
+            .push_timelock(self.exit_timelock()) // Relative or Absolute Timelock
+            .push_key(&self.self_key()) // Self Key 32-bytes
+            .push_opcode(OP_CHECKSIG)
+            .into_script();
+        let exit_path = TapLeaf::new(exit_path_script);
+
+        Ok(TapRoot::key_and_script_path_single(inner_key, exit_path))
+    }
+
+    fn spk(&self) -> Result<Bytes, secp256k1::Error> {
+        self.taproot()?.spk()
+    }
+}
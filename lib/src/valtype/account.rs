@@ -1,14 +1,21 @@
 #![allow(dead_code)]
 
 use super::value::ShortVal;
-use crate::serialization::cpe::CompactPayloadEncoding;
-use bit_vec::BitVec;
+use crate::serialization::{
+    bits::{BitReader, BitWriter},
+    cpe::{CPEContext, CPEDecodingError, CompactPayloadDecoding, CompactPayloadEncoding},
+};
 use musig2::secp256k1::XOnlyPublicKey;
 
 type Key = XOnlyPublicKey;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Account {
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serialization::serde_hex::key")
+    )]
     key: Key,
     account_index: Option<u32>,
 }
@@ -42,30 +49,47 @@ impl Account {
 }
 
 impl CompactPayloadEncoding for Account {
-    fn to_cpe(&self) -> BitVec {
-        let mut bit_vec = BitVec::new();
-
+    fn write_cpe(&self, writer: &mut BitWriter) {
         match self.account_index {
             None => {
                 // Non-compact form
-                bit_vec.push(false);
-
-                let key_array = self.key.serialize();
-                let key_bits = BitVec::from_bytes(&key_array);
-
-                bit_vec.extend(key_bits);
+                writer.write_bit(false);
+                writer.write_bytes(&self.key.serialize());
             }
             Some(index) => {
                 // Compact form
-                bit_vec.push(true);
+                writer.write_bit(true);
 
                 // ShortVal represents compact integer forms
-                let index_compact = ShortVal(index);
-
-                bit_vec.extend(index_compact.to_cpe());
+                ShortVal(index).write_cpe(writer);
             }
         }
+    }
+}
 
-        bit_vec
+impl CompactPayloadDecoding for Account {
+    fn from_cpe(
+        reader: &mut BitReader,
+        context: &impl CPEContext,
+    ) -> Result<Account, CPEDecodingError> {
+        match reader.read_bit()? {
+            false => {
+                // Non-compact form
+                let key_bytes = reader.read_bytes(32)?;
+                let key =
+                    Key::from_slice(&key_bytes).map_err(|_| CPEDecodingError::KeyParseError)?;
+
+                Ok(Account::new(key))
+            }
+            true => {
+                // Compact form
+                let index = ShortVal::from_cpe(reader, context)?.value();
+                let key = context
+                    .account_key(index)
+                    .ok_or(CPEDecodingError::AccountIndexError(index))?;
+
+                Ok(Account::new_compact(key, index))
+            }
+        }
     }
 }
@@ -1,11 +1,18 @@
 #![allow(dead_code)]
 
 use super::value::ShortVal;
-use crate::serialization::cpe::CompactPayloadEncoding;
-use bit_vec::BitVec;
+use crate::serialization::{
+    bits::{BitReader, BitWriter},
+    cpe::{CPEContext, CPEDecodingError, CompactPayloadDecoding, CompactPayloadEncoding},
+};
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Contract {
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serialization::serde_hex::bytes32")
+    )]
     contract_id: [u8; 32],
     contract_index: Option<u32>,
 }
@@ -39,30 +46,46 @@ impl Contract {
 }
 
 impl CompactPayloadEncoding for Contract {
-    fn to_cpe(&self) -> BitVec {
-        let mut bit_vec = BitVec::new();
-
+    fn write_cpe(&self, writer: &mut BitWriter) {
         match self.contract_index {
             None => {
                 // Non-compact form
-                bit_vec.push(false);
-
-                let id_array = self.contract_id;
-                let id_bits = BitVec::from_bytes(&id_array);
-
-                bit_vec.extend(id_bits);
+                writer.write_bit(false);
+                writer.write_bytes(&self.contract_id);
             }
             Some(index) => {
                 // Compact form
-                bit_vec.push(true);
-
-                // ShortAmount represents compact integer forms
-                let index_compact = ShortVal(index);
+                writer.write_bit(true);
 
-                bit_vec.extend(index_compact.to_cpe());
+                // ShortVal represents compact integer forms
+                ShortVal(index).write_cpe(writer);
             }
         }
+    }
+}
 
-        bit_vec
+impl CompactPayloadDecoding for Contract {
+    fn from_cpe(
+        reader: &mut BitReader,
+        context: &impl CPEContext,
+    ) -> Result<Contract, CPEDecodingError> {
+        match reader.read_bit()? {
+            false => {
+                // Non-compact form
+                let mut contract_id = [0x00u8; 32];
+                contract_id.copy_from_slice(&reader.read_bytes(32)?);
+
+                Ok(Contract::new(contract_id))
+            }
+            true => {
+                // Compact form
+                let index = ShortVal::from_cpe(reader, context)?.value();
+                let contract_id = context
+                    .contract_id(index)
+                    .ok_or(CPEDecodingError::ContractIndexError(index))?;
+
+                Ok(Contract::new_compact(contract_id, index))
+            }
+        }
     }
 }
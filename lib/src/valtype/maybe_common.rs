@@ -2,41 +2,101 @@
 
 use bit_vec::BitVec;
 
-use crate::serialization::{cpe::CommonIndex, cpe::CompactPayloadEncoding};
+use crate::serialization::{
+    bits::{BitReader, BitWriter},
+    cpe::{
+        CPEContext, CPEDecodingError, CommonIndex, CompactPayloadDecoding, CompactPayloadEncoding,
+    },
+};
 use std::u8;
 
-pub trait MaybeCommonType {}
+pub trait MaybeCommonType: Sized {
+    // Resolve the value referenced by a 3-bit common index.
+    fn from_common_index(context: &impl CPEContext, common_index: u8) -> Option<Self>;
+}
+
+impl MaybeCommonType for super::account::Account {
+    fn from_common_index(context: &impl CPEContext, common_index: u8) -> Option<Self> {
+        context.common_account(common_index)
+    }
+}
 
-impl MaybeCommonType for super::account::Account {}
-impl MaybeCommonType for super::contract::Contract {}
-impl MaybeCommonType for super::value::ShortVal {}
-impl MaybeCommonType for super::value::LongVal {}
+impl MaybeCommonType for super::contract::Contract {
+    fn from_common_index(context: &impl CPEContext, common_index: u8) -> Option<Self> {
+        context.common_contract(common_index)
+    }
+}
 
-#[derive(Clone, Copy)]
+impl MaybeCommonType for super::value::ShortVal {
+    fn from_common_index(context: &impl CPEContext, common_index: u8) -> Option<Self> {
+        context.common_short_val(common_index)
+    }
+}
+
+impl MaybeCommonType for super::value::LongVal {
+    fn from_common_index(context: &impl CPEContext, common_index: u8) -> Option<Self> {
+        context.common_long_val(common_index)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MaybeCommon<T: MaybeCommonType> {
     Common(T, u8),
     Uncommon(T),
 }
 
-impl<T: MaybeCommonType + CompactPayloadEncoding> CompactPayloadEncoding for MaybeCommon<T> {
-    fn to_cpe(&self) -> BitVec {
-        let mut bit_vec = BitVec::new();
+impl<T: MaybeCommonType + Copy> MaybeCommon<T> {
+    // The referenced value, regardless of whether it is common.
+    pub fn value(&self) -> T {
+        match self {
+            MaybeCommon::Common(value, _) => *value,
+            MaybeCommon::Uncommon(value) => *value,
+        }
+    }
 
+    pub fn common_index(&self) -> Option<u8> {
+        match self {
+            MaybeCommon::Common(_, common_index) => Some(*common_index),
+            MaybeCommon::Uncommon(_) => None,
+        }
+    }
+}
+
+impl<T: MaybeCommonType + CompactPayloadEncoding> CompactPayloadEncoding for MaybeCommon<T> {
+    fn write_cpe(&self, writer: &mut BitWriter) {
         match self {
             MaybeCommon::Uncommon(uncommon) => {
                 // Common bit = false
-                bit_vec.push(false);
+                writer.write_bit(false);
                 // Bit-encoding:
-                bit_vec.extend(uncommon.to_cpe());
-                bit_vec
+                uncommon.write_cpe(writer);
             }
             MaybeCommon::Common(_, common_index) => {
                 // Common bit = true
-                bit_vec.push(true);
+                writer.write_bit(true);
                 // 3-bit common index encoding:
-                bit_vec.extend(BitVec::from_u8_common_index(common_index));
-                bit_vec
+                writer.write_bit_vec(&BitVec::from_u8_common_index(common_index));
             }
         }
     }
-}
\ No newline at end of file
+}
+
+impl<T: MaybeCommonType + CompactPayloadDecoding> CompactPayloadDecoding for MaybeCommon<T> {
+    fn from_cpe(
+        reader: &mut BitReader,
+        context: &impl CPEContext,
+    ) -> Result<Self, CPEDecodingError> {
+        match reader.read_bit()? {
+            false => Ok(MaybeCommon::Uncommon(T::from_cpe(reader, context)?)),
+            true => {
+                // 3-bit common index decoding:
+                let common_index = reader.read_bit_vec(3)?.to_u8_common_index();
+                let common = T::from_common_index(context, common_index)
+                    .ok_or(CPEDecodingError::CommonIndexError(common_index))?;
+
+                Ok(MaybeCommon::Common(common, common_index))
+            }
+        }
+    }
+}
@@ -1,4 +1,5 @@
 pub mod account;
 pub mod contract;
 pub mod maybe_common;
-pub mod value;
\ No newline at end of file
+pub mod outpoint;
+pub mod value;
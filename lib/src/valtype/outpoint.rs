@@ -0,0 +1,82 @@
+#![allow(dead_code)]
+
+use std::io::{Read, Write};
+
+use super::value::ShortVal;
+use crate::serialization::{
+    bits::{BitReader, BitWriter},
+    cpe::{CPEContext, CPEDecodingError, CompactPayloadDecoding, CompactPayloadEncoding},
+    serialize::{write_all, ByteReader, Serialize, SerializeError},
+};
+
+// Reference to an on-chain transaction output.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Outpoint {
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serialization::serde_hex::bytes32")
+    )]
+    txid: [u8; 32],
+    vout: u32,
+}
+
+impl Outpoint {
+    pub fn new(txid: [u8; 32], vout: u32) -> Outpoint {
+        Outpoint { txid, vout }
+    }
+
+    pub fn txid(&self) -> [u8; 32] {
+        self.txid
+    }
+
+    pub fn vout(&self) -> u32 {
+        self.vout
+    }
+}
+
+impl CompactPayloadEncoding for Outpoint {
+    fn write_cpe(&self, writer: &mut BitWriter) {
+        // Txid
+        writer.write_bytes(&self.txid);
+
+        // Vout
+        ShortVal(self.vout).write_cpe(writer);
+    }
+}
+
+impl CompactPayloadDecoding for Outpoint {
+    fn from_cpe(
+        reader: &mut BitReader,
+        context: &impl CPEContext,
+    ) -> Result<Outpoint, CPEDecodingError> {
+        // Txid
+        let mut txid = [0x00u8; 32];
+        txid.copy_from_slice(&reader.read_bytes(32)?);
+
+        // Vout
+        let vout = ShortVal::from_cpe(reader, context)?.value();
+
+        Ok(Outpoint::new(txid, vout))
+    }
+}
+
+impl Serialize for Outpoint {
+    fn write_to<W: Write>(&self, writer: &mut W) -> Result<(), SerializeError> {
+        // Txid
+        write_all(writer, &self.txid)?;
+
+        // Vout
+        write_all(writer, &self.vout.to_le_bytes())
+    }
+
+    fn read_from<R: Read>(reader: &mut ByteReader<R>) -> Result<Outpoint, SerializeError> {
+        // Txid
+        let txid = reader.read_array::<32>()?;
+
+        // Vout
+        let vout = reader.read_u32_le()?;
+
+        Ok(Outpoint::new(txid, vout))
+    }
+}
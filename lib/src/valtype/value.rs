@@ -1,13 +1,17 @@
 #![allow(dead_code)]
 
-use crate::serialization::cpe::CompactPayloadEncoding;
-use bit_vec::BitVec;
+use crate::serialization::{
+    bits::{BitReader, BitWriter},
+    cpe::{CPEContext, CPEDecodingError, CompactPayloadDecoding, CompactPayloadEncoding},
+};
 use uintx::{u24, u40, u48, u56};
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ShortVal(pub u32);
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LongVal(pub u64);
 
 impl ShortVal {
@@ -31,113 +35,121 @@ impl LongVal {
 }
 
 impl CompactPayloadEncoding for ShortVal {
-    fn to_cpe(&self) -> BitVec {
+    fn write_cpe(&self, writer: &mut BitWriter) {
         let value = self.0;
-        let mut bit_vec = BitVec::new();
 
         match value {
             0..=255 => {
                 // b00 -> UInt 8 (1-byte)
-                bit_vec.push(false);
-                bit_vec.push(false);
-
-                let val_byte = vec![value as u8];
-                let val_bits = BitVec::from_bytes(&val_byte);
-                bit_vec.extend(val_bits);
+                writer.write_uint(0b00, 2);
+                writer.write_bytes(&[value as u8]);
             }
 
             256..=65535 => {
                 // b01 -> UInt 16 (2 bytes)
-                bit_vec.push(false);
-                bit_vec.push(true);
+                writer.write_uint(0b01, 2);
 
                 let val_bytes: [u8; 2] = (value as u16).to_le_bytes();
-                let val_bits = BitVec::from_bytes(&val_bytes);
-                bit_vec.extend(val_bits);
+                writer.write_bytes(&val_bytes);
             }
 
             65536..=16777215 => {
                 // b10 -> UInt 24 (3 bytes)
-                bit_vec.push(true);
-                bit_vec.push(false);
+                writer.write_uint(0b10, 2);
 
                 let val_bytes: [u8; 3] = u24::from(value).to_le_bytes();
-                let val_bits = BitVec::from_bytes(&val_bytes);
-                bit_vec.extend(val_bits);
+                writer.write_bytes(&val_bytes);
             }
 
             16777216..=4294967295 => {
                 // b11 -> UInt 32 (4 bytes)
-                bit_vec.push(true);
-                bit_vec.push(true);
+                writer.write_uint(0b11, 2);
 
-                let val_bytes: [u8; 4] = (value as u32).to_le_bytes();
-                let val_bits = BitVec::from_bytes(&val_bytes);
-                bit_vec.extend(val_bits);
+                let val_bytes: [u8; 4] = value.to_le_bytes();
+                writer.write_bytes(&val_bytes);
             }
         }
-
-        bit_vec
     }
 }
 
 impl CompactPayloadEncoding for LongVal {
-    fn to_cpe(&self) -> BitVec {
+    fn write_cpe(&self, writer: &mut BitWriter) {
         let value = self.0;
-        let mut bit_vec = BitVec::new();
 
         match value {
             0..=4294967295 => {
                 // Interpet as Short Val and cast to Long Val by appending a zero-bit prefix
-                bit_vec.push(false);
-                bit_vec.extend(ShortVal(value as u32).to_cpe());
+                writer.write_bit(false);
+                ShortVal(value as u32).write_cpe(writer);
             }
 
             4294967296..=1099511627775 => {
                 // b100 -> UInt 40 (5 bytes)
-                bit_vec.push(true);
-                bit_vec.push(false);
-                bit_vec.push(false);
+                writer.write_uint(0b100, 3);
 
                 let val_bytes: [u8; 5] = u40::from(value).to_le_bytes();
-                let val_bits = BitVec::from_bytes(&val_bytes);
-                bit_vec.extend(val_bits);
+                writer.write_bytes(&val_bytes);
             }
 
             1099511627776..=281474976710655 => {
                 // b101 -> UInt 48 (6 bytes)
-                bit_vec.push(true);
-                bit_vec.push(false);
-                bit_vec.push(true);
+                writer.write_uint(0b101, 3);
 
                 let val_bytes: [u8; 6] = u48::from(value).to_le_bytes();
-                let val_bits = BitVec::from_bytes(&val_bytes);
-                bit_vec.extend(val_bits);
+                writer.write_bytes(&val_bytes);
             }
 
             281474976710656..=72057594037927935 => {
                 // b110 -> UInt 56 (7 bytes)
-                bit_vec.push(true);
-                bit_vec.push(true);
-                bit_vec.push(false);
+                writer.write_uint(0b110, 3);
 
                 let val_bytes: [u8; 7] = u56::from(value).to_le_bytes();
-                let val_bits = BitVec::from_bytes(&val_bytes);
-                bit_vec.extend(val_bits);
+                writer.write_bytes(&val_bytes);
             }
 
             72057594037927936..=18446744073709551615 => {
                 // b111 -> UInt 64 (8 bytes)
-                bit_vec.push(true);
-                bit_vec.push(true);
-                bit_vec.push(true);
+                writer.write_uint(0b111, 3);
 
                 let val_bytes: [u8; 8] = value.to_le_bytes();
-                let val_bits = BitVec::from_bytes(&val_bytes);
-                bit_vec.extend(val_bits);
+                writer.write_bytes(&val_bytes);
             }
         }
+    }
+}
+
+impl CompactPayloadDecoding for ShortVal {
+    fn from_cpe(
+        reader: &mut BitReader,
+        _context: &impl CPEContext,
+    ) -> Result<ShortVal, CPEDecodingError> {
+        // 2-bit length prefix: b00 -> 1 byte, b01 -> 2 bytes, b10 -> 3 bytes, b11 -> 4 bytes
+        let num_bytes = reader.read_uint(2)? as usize + 1;
+
+        let mut val_bytes = [0x00u8; 4];
+        val_bytes[..num_bytes].copy_from_slice(&reader.read_bytes(num_bytes)?);
+
+        Ok(ShortVal(u32::from_le_bytes(val_bytes)))
+    }
+}
+
+impl CompactPayloadDecoding for LongVal {
+    fn from_cpe(
+        reader: &mut BitReader,
+        context: &impl CPEContext,
+    ) -> Result<LongVal, CPEDecodingError> {
+        // Zero-bit prefix -> Short Val cast to Long Val
+        if !reader.read_bit()? {
+            let short_val = ShortVal::from_cpe(reader, context)?;
+            return Ok(LongVal(short_val.value() as u64));
+        }
+
+        // 2-bit length prefix: b100 -> 5 bytes, b101 -> 6 bytes, b110 -> 7 bytes, b111 -> 8 bytes
+        let num_bytes = reader.read_uint(2)? as usize + 5;
+
+        let mut val_bytes = [0x00u8; 8];
+        val_bytes[..num_bytes].copy_from_slice(&reader.read_bytes(num_bytes)?);
 
-        bit_vec
+        Ok(LongVal(u64::from_le_bytes(val_bytes)))
     }
 }
@@ -0,0 +1,284 @@
+#[cfg(test)]
+mod covenant_tests {
+    use brollup::{
+        covenant::{verify_payload, Covenant, CovenantError, CONNECTOR_VALUE},
+        taproot::P2TR,
+        tx::pool::PoolTransaction,
+        txo::{
+            connector::Connector,
+            payload::Payload,
+            projector::{Projector, ProjectorTag},
+            vtxo::VTXO,
+        },
+        valtype::outpoint::Outpoint,
+    };
+    use musig2::{
+        aggregate_partial_signatures,
+        secp256k1::{Parity, Secp256k1, SecretKey, XOnlyPublicKey},
+        sign_partial, AggNonce, PartialSignature, SecNonce,
+    };
+
+    // Secret key whose even-lifted public key is the x-only key.
+    fn secret_key(seed: u8) -> SecretKey {
+        let secret_key = SecretKey::from_slice(&[seed; 32]).unwrap();
+        match secret_key.x_only_public_key(&Secp256k1::new()).1 {
+            Parity::Even => secret_key,
+            Parity::Odd => secret_key.negate(),
+        }
+    }
+
+    fn key(seed: u8) -> XOnlyPublicKey {
+        secret_key(seed).x_only_public_key(&Secp256k1::new()).0
+    }
+
+    fn projector(tag: ProjectorTag) -> Projector {
+        Projector::new_with_operator(vec![key(1), key(2)], key(3), tag)
+    }
+
+    fn vtxo_covenant(values: &[u64]) -> Covenant {
+        let vtxos: Vec<(VTXO, u64)> = values
+            .iter()
+            .enumerate()
+            .map(|(index, value)| {
+                (
+                    VTXO::new_with_operator(key(index as u8 + 1), key(3)),
+                    *value,
+                )
+            })
+            .collect();
+
+        Covenant::vtxos(
+            projector(ProjectorTag::VTXOProjector),
+            Outpoint::new([0x01; 32], 1),
+            &vtxos,
+        )
+        .unwrap()
+    }
+
+    fn connector_covenant() -> Covenant {
+        let connectors = vec![
+            Connector::new_with_operator(key(1), key(3)),
+            Connector::new_with_operator(key(2), key(3)),
+        ];
+
+        Covenant::connectors(
+            projector(ProjectorTag::ConnectorProjector),
+            Outpoint::new([0x01; 32], 2),
+            &connectors,
+        )
+        .unwrap()
+    }
+
+    // MuSig2 session between msg.senders[] and the operator.
+    fn musig2_sign(covenant: &Covenant) -> [u8; 64] {
+        let key_agg_ctx = covenant.key_agg_ctx().unwrap();
+        let sighash = covenant.sighash().unwrap();
+
+        let signers: Vec<(SecretKey, SecNonce)> = [1u8, 2, 3]
+            .iter()
+            .map(|seed| {
+                let secnonce = SecNonce::build([*seed + 100; 32])
+                    .with_message(&sighash)
+                    .build();
+                (secret_key(*seed), secnonce)
+            })
+            .collect();
+
+        let agg_nonce = AggNonce::sum(signers.iter().map(|(_, secnonce)| secnonce.public_nonce()));
+
+        let partial_signatures: Vec<PartialSignature> = signers
+            .into_iter()
+            .map(|(secret_key, secnonce)| {
+                sign_partial(&key_agg_ctx, secret_key, secnonce, &agg_nonce, sighash).unwrap()
+            })
+            .collect();
+
+        aggregate_partial_signatures(&key_agg_ctx, &agg_nonce, partial_signatures, sighash).unwrap()
+    }
+
+    #[test]
+    fn test_covenant_fan_out() {
+        let covenant = vtxo_covenant(&[10_000, 25_000, 330]);
+        assert_eq!(covenant.value(), Ok(35_330));
+
+        let tx = covenant.transaction();
+        assert_eq!(tx.inputs().len(), 1);
+        assert_eq!(tx.inputs()[0].outpoint(), Outpoint::new([0x01; 32], 1));
+
+        let outputs = tx.outputs();
+        assert_eq!(outputs.len(), 3);
+        assert_eq!(outputs[1].value(), 25_000);
+        assert_eq!(
+            outputs[1].spk(),
+            VTXO::new_with_operator(key(2), key(3)).spk().unwrap()
+        );
+
+        let covenant = connector_covenant();
+        assert_eq!(covenant.value(), Ok(2 * CONNECTOR_VALUE));
+
+        // The projector tag must match the fan-out.
+        assert_eq!(
+            Covenant::connectors(
+                projector(ProjectorTag::VTXOProjector),
+                Outpoint::new([0x01; 32], 1),
+                &[]
+            )
+            .err(),
+            Some(CovenantError::ProjectorTagMismatch)
+        );
+        assert_eq!(
+            Covenant::vtxos(
+                projector(ProjectorTag::VTXOProjector),
+                Outpoint::new([0x01; 32], 1),
+                &[
+                    (VTXO::new_with_operator(key(1), key(3)), u64::MAX),
+                    (VTXO::new_with_operator(key(2), key(3)), 1)
+                ]
+            )
+            .err(),
+            Some(CovenantError::ValueOverflow)
+        );
+    }
+
+    #[test]
+    fn test_covenant_pool_outputs() {
+        let vtxos = vec![
+            (VTXO::new_with_operator(key(1), key(3)), 10_000),
+            (VTXO::new_with_operator(key(2), key(3)), 25_000),
+        ];
+        let connectors = vec![
+            Connector::new_with_operator(key(1), key(3)),
+            Connector::new_with_operator(key(2), key(3)),
+            Connector::new_with_operator(key(4), key(3)),
+        ];
+
+        let payload = Payload::new(
+            vec![key(1), key(2)],
+            key(3),
+            vec![],
+            25,
+            10,
+            key(3),
+            [0xaa; 64],
+            [0xbb; 64],
+            vec![],
+        );
+        let pool_tx = PoolTransaction::new(
+            payload,
+            projector(ProjectorTag::VTXOProjector),
+            35_000,
+            projector(ProjectorTag::ConnectorProjector),
+            connectors.len(),
+            key(3),
+        )
+        .spend_lift(Outpoint::new([0x02; 32], 0), 100_000);
+        assert_eq!(pool_tx.connector_projector_value(), Ok(3 * CONNECTOR_VALUE));
+
+        let tx = pool_tx.build().unwrap();
+        let txid = tx.txid();
+
+        let vtxo_covenant = Covenant::vtxos(
+            projector(ProjectorTag::VTXOProjector),
+            Outpoint::new(txid, 1),
+            &vtxos,
+        )
+        .unwrap();
+        let connector_covenant = Covenant::connectors(
+            projector(ProjectorTag::ConnectorProjector),
+            Outpoint::new(txid, 2),
+            &connectors,
+        )
+        .unwrap();
+
+        // Each fan-out spends its projector output in the pool transaction, at exactly its value.
+        let outputs = tx.outputs();
+        for (covenant, vout) in [(&vtxo_covenant, 1u32), (&connector_covenant, 2)] {
+            let fan_out = covenant.transaction();
+            assert_eq!(fan_out.inputs()[0].outpoint(), Outpoint::new(txid, vout));
+
+            let spent = &outputs[vout as usize];
+            assert_eq!(covenant.value(), Ok(spent.value()));
+            assert_eq!(spent.spk(), covenant.projector().spk().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_covenant_signatures() {
+        let covenant = vtxo_covenant(&[10_000, 25_000]);
+
+        // The MuSig2 context aggregates to the projector output key.
+        let output_key: XOnlyPublicKey = covenant.key_agg_ctx().unwrap().aggregated_pubkey();
+        assert_eq!(
+            output_key,
+            covenant
+                .projector()
+                .taproot()
+                .unwrap()
+                .tweaked_key_x_only()
+                .unwrap()
+        );
+
+        let signature = musig2_sign(&covenant);
+        assert_eq!(covenant.verify(signature), Ok(()));
+
+        // The signature commits to the outputs and to the projector outpoint.
+        assert_eq!(
+            vtxo_covenant(&[10_000, 25_001]).verify(signature),
+            Err(CovenantError::InvalidSignature)
+        );
+
+        let moved = Covenant::vtxos(
+            projector(ProjectorTag::VTXOProjector),
+            Outpoint::new([0x01; 32], 3),
+            &[
+                (VTXO::new_with_operator(key(1), key(3)), 10_000),
+                (VTXO::new_with_operator(key(2), key(3)), 25_000),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            moved.verify(signature),
+            Err(CovenantError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn test_covenant_verify_payload() {
+        let vtxo_covenant = vtxo_covenant(&[10_000, 25_000]);
+        let connector_covenant = connector_covenant();
+
+        let vtxo_signature = musig2_sign(&vtxo_covenant);
+        let connector_signature = musig2_sign(&connector_covenant);
+
+        let payload_tapscript = |vtxo_signature: [u8; 64], connector_signature: [u8; 64]| {
+            let payload = Payload::new(
+                vec![key(1), key(2)],
+                key(3),
+                vec![[0x11; 32], [0x22; 32]],
+                25,
+                10,
+                key(4),
+                vtxo_signature,
+                connector_signature,
+                vec![],
+            );
+
+            payload.taproot().unwrap().tree().unwrap().leaves()[0].tap_script()
+        };
+
+        let tap_script = payload_tapscript(vtxo_signature, connector_signature);
+        let payload = Payload::from_tapscript(&tap_script, &()).unwrap();
+        assert_eq!(
+            verify_payload(&payload, &vtxo_covenant, &connector_covenant),
+            Ok(())
+        );
+
+        // Swapped signatures
+        let tap_script = payload_tapscript(connector_signature, vtxo_signature);
+        let payload = Payload::from_tapscript(&tap_script, &()).unwrap();
+        assert_eq!(
+            verify_payload(&payload, &vtxo_covenant, &connector_covenant),
+            Err(CovenantError::InvalidSignature)
+        );
+    }
+}
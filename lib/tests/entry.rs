@@ -0,0 +1,734 @@
+#[cfg(test)]
+mod entry_tests {
+    use brollup::{
+        entry::{
+            call::Call,
+            compressor::{compress_entries, CommonTables},
+            entry::Entry,
+            liftdown::Liftdown,
+            liftup::Liftup,
+            recharge::Recharge,
+            transfer::Transfer,
+        },
+        registry::{
+            account::AccountRegistry, context::RegistryContext, contract::ContractRegistry,
+        },
+        serialization::{
+            bits::{BitReader, BitWriter},
+            cpe::{CPEContext, CompactPayloadDecoding, CompactPayloadEncoding},
+            serialize::{ByteReader, Serialize, SerializeError},
+            sighash::Sighash,
+        },
+        signature::schnorr::{Sign, Verify},
+        valtype::{
+            account::Account,
+            contract::Contract,
+            maybe_common::MaybeCommon,
+            outpoint::Outpoint,
+            value::{LongVal, ShortVal},
+        },
+    };
+    use musig2::secp256k1::{Secp256k1, SecretKey, XOnlyPublicKey};
+
+    fn key(seed: u8) -> XOnlyPublicKey {
+        let secret_key = SecretKey::from_slice(&[seed; 32]).unwrap();
+        secret_key.x_only_public_key(&Secp256k1::new()).0
+    }
+
+    fn transfer(from: u8, to: u8, amount: u64) -> Entry {
+        Entry::Transfer(Transfer::new_uncommon(
+            Account::new(key(from)),
+            0,
+            Account::new(key(to)),
+            LongVal::new(amount),
+        ))
+    }
+
+    fn call(caller: u8, contract_id: [u8; 32], args: Vec<Vec<u8>>, value: u32) -> Entry {
+        Entry::Call(Call::new_uncommon(
+            Account::new(key(caller)),
+            0,
+            Contract::new(contract_id),
+            0x07,
+            args,
+            ShortVal::new(value),
+        ))
+    }
+
+    fn roundtrip(entries: &[Entry], context: &impl CPEContext) -> Vec<Entry> {
+        let mut writer = BitWriter::new();
+        for entry in entries {
+            entry.write_cpe(&mut writer);
+        }
+
+        let bytes = writer.to_bytes();
+        let mut reader = BitReader::from_padded(&bytes, writer.padding_len()).unwrap();
+
+        let mut decoded = Vec::<Entry>::new();
+        while !reader.is_empty() {
+            decoded.push(Entry::from_cpe(&mut reader, context).unwrap());
+        }
+
+        decoded
+    }
+
+    #[test]
+    fn test_compress_entries() {
+        // Same recipient and amount twice
+        let entries = vec![transfer(1, 2, 1000), transfer(3, 2, 1000)];
+
+        let compressed = compress_entries(&entries);
+        let common_tables = compressed.common_tables();
+
+        assert_eq!(common_tables.accounts(), vec![Account::new(key(2))]);
+        assert_eq!(common_tables.long_vals(), vec![LongVal::new(1000)]);
+
+        // Transfer: 2 + from (257) + nonce (10) + to (1 + 257) + amount (1 + 19) + fee (1) = 548 bits
+        // Common references take 1 + 3 bits each: 2 + 257 + 10 + 4 + 4 + 1 = 278 bits
+        // Table entries: to (257) + amount (19) = 276 bits
+        assert_eq!(compressed.bits_before(), 1096);
+        assert_eq!(compressed.bits_after(), 556);
+        assert_eq!(compressed.table_bits(), 276);
+        assert_eq!(compressed.bits_saved(), 264);
+
+        for entry in compressed.entries() {
+            let Entry::Transfer(transfer) = entry else {
+                panic!("Expected a transfer.");
+            };
+            assert_eq!(transfer.to(), MaybeCommon::Common(Account::new(key(2)), 0));
+            assert_eq!(
+                transfer.amount(),
+                MaybeCommon::Common(LongVal::new(1000), 0)
+            );
+        }
+
+        assert_eq!(
+            roundtrip(&compressed.entries(), &common_tables),
+            compressed.entries()
+        );
+
+        // Common indexes set by the caller are re-assigned.
+        let mut entries = entries;
+        let Entry::Transfer(transfer) = &mut entries[0] else {
+            panic!("Expected a transfer.");
+        };
+        transfer.set_amount_common_index(5);
+        assert_eq!(compress_entries(&entries), compressed);
+    }
+
+    #[test]
+    fn test_compress_entries_table_limit() {
+        // 10 recipients and 10 amounts, each seen a different number of times
+        let mut entries = Vec::<Entry>::new();
+        for i in 0..10u8 {
+            for _ in 0..=i {
+                entries.push(transfer(100, 10 + i, 1_000_000 + i as u64));
+            }
+        }
+
+        let compressed = compress_entries(&entries);
+        let common_tables = compressed.common_tables();
+
+        // The 8 most frequent, most frequent first
+        let expected_accounts: Vec<Account> =
+            (2..10u8).rev().map(|i| Account::new(key(10 + i))).collect();
+        let expected_long_vals: Vec<LongVal> = (2..10u64)
+            .rev()
+            .map(|i| LongVal::new(1_000_000 + i))
+            .collect();
+
+        assert_eq!(common_tables.accounts(), expected_accounts);
+        assert_eq!(common_tables.long_vals(), expected_long_vals);
+
+        // The least frequent remain uncommon.
+        let Entry::Transfer(first) = compressed.entries()[0] else {
+            panic!("Expected a transfer.");
+        };
+        assert_eq!(first.to(), MaybeCommon::Uncommon(Account::new(key(10))));
+
+        assert!(compressed.bits_after() < compressed.bits_before());
+        assert_eq!(
+            roundtrip(&compressed.entries(), &common_tables),
+            compressed.entries()
+        );
+
+        // Values seen once do not pay for their table entry.
+        let compressed = compress_entries(&[transfer(1, 2, 1000)]);
+        assert!(compressed.common_tables().is_empty());
+        assert_eq!(compressed.entries(), vec![transfer(1, 2, 1000)]);
+        assert_eq!(compressed.bits_saved(), 0);
+
+        // Common references fail to resolve without the tables.
+        let compressed = compress_entries(&[transfer(1, 2, 1000), transfer(3, 2, 1000)]);
+        let bytes = compressed.entries()[0].to_cpe().to_bytes();
+        let mut reader = BitReader::new(&bytes);
+        assert!(Entry::from_cpe(&mut reader, &()).is_err());
+    }
+
+    #[test]
+    fn test_call_entry() {
+        let args = vec![vec![0xde, 0xad], vec![], vec![0xff; 300]];
+        let entry = call(1, [0x33; 32], args.clone(), 5000);
+
+        // CPE roundtrip against registries
+        let mut accounts = AccountRegistry::new();
+        accounts.register(key(1));
+        let mut contracts = ContractRegistry::new();
+        contracts.register([0x33; 32]);
+
+        let compacted = accounts.compact_entries(std::slice::from_ref(&entry));
+        let Entry::Call(mut compact_call) = compacted[0].clone() else {
+            panic!("Expected a call.");
+        };
+        assert_eq!(compact_call.caller(), Account::new_compact(key(1), 0));
+        compact_call.set_contract_index(0);
+
+        let context = RegistryContext::new(accounts, contracts);
+        let compact_entry = Entry::Call(compact_call);
+        let bytes = compact_entry.to_cpe().to_bytes();
+        let mut reader = BitReader::new(&bytes);
+        assert_eq!(
+            Entry::from_cpe(&mut reader, &context).unwrap(),
+            compact_entry
+        );
+
+        // Compact forms fail to resolve without the registries.
+        let mut reader = BitReader::new(&bytes);
+        assert!(Entry::from_cpe(&mut reader, &()).is_err());
+
+        // Mixed with transfers
+        let entries = vec![transfer(1, 2, 1000), entry.clone(), transfer(3, 4, 2000)];
+        assert_eq!(roundtrip(&entries, &CommonTables::default()), entries);
+
+        // Byte serialization
+        let Entry::Call(call_entry) = &entry else {
+            panic!("Expected a call.");
+        };
+        let bytes = call_entry.serialize();
+        assert_eq!(bytes[0], 0x81);
+
+        // The nonce follows the caller, even when zero.
+        assert_eq!(bytes[33..37], 0u32.to_le_bytes());
+        assert_eq!(
+            bytes.len(),
+            1 + 32 + 4 + 32 + 1 + 1 + (1 + 2) + 1 + (3 + 300) + 4
+        );
+        assert_eq!(Call::from_bytes(&bytes).unwrap(), *call_entry);
+
+        assert_eq!(
+            Call::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(SerializeError::UnexpectedEof {
+                offset: bytes.len() - 1
+            })
+        );
+
+        let mut wrong_type = bytes.clone();
+        wrong_type[0] = 0x00;
+        assert_eq!(
+            Call::from_bytes(&wrong_type),
+            Err(SerializeError::EntryTypeError {
+                offset: 0,
+                entry_type: 0x00
+            })
+        );
+
+        // A bogus argument count must fail rather than allocate.
+        let mut bogus_count = bytes[..70].to_vec();
+        bogus_count.extend([0x07, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]);
+        assert!(Call::from_bytes(&bogus_count).is_err());
+
+        // Sighash commits to the entry type and every field.
+        let transfer_entry = transfer(1, 2, 5000);
+        assert_ne!(
+            entry.sighash([0x00; 32]),
+            transfer_entry.sighash([0x00; 32])
+        );
+        assert_ne!(
+            entry.sighash([0x00; 32]),
+            call(1, [0x33; 32], vec![vec![0xde, 0xae]], 5000).sighash([0x00; 32])
+        );
+        assert_ne!(entry.sighash([0x00; 32]), entry.sighash([0x01; 32]));
+
+        assert!(entry.sign([0x01; 32], [0x00; 32]).is_ok());
+    }
+
+    #[test]
+    fn test_compress_entries_with_calls() {
+        let entries = vec![
+            transfer(1, 2, 1000),
+            call(3, [0x33; 32], vec![vec![0x01]], 1000),
+            call(4, [0x33; 32], vec![], 1000),
+        ];
+
+        let compressed = compress_entries(&entries);
+        let common_tables = compressed.common_tables();
+
+        // Calls share their value with each other, not with the transfer amount.
+        assert_eq!(common_tables.short_vals(), vec![ShortVal::new(1000)]);
+        assert!(common_tables.long_vals().is_empty());
+        assert_eq!(common_tables.contracts(), vec![Contract::new([0x33; 32])]);
+
+        let Entry::Call(compressed_call) = compressed.entries()[1].clone() else {
+            panic!("Expected a call.");
+        };
+        assert_eq!(
+            compressed_call.value(),
+            MaybeCommon::Common(ShortVal::new(1000), 0)
+        );
+        assert_eq!(
+            compressed_call.contract(),
+            MaybeCommon::Common(Contract::new([0x33; 32]), 0)
+        );
+
+        assert_eq!(
+            roundtrip(&compressed.entries(), &common_tables),
+            compressed.entries()
+        );
+
+        // Calls that start with a common contract have it re-assigned.
+        let common_call = |caller: u8, common_index: u8| {
+            Entry::Call(Call::new(
+                Account::new(key(caller)),
+                0,
+                MaybeCommon::Common(Contract::new([0x44; 32]), common_index),
+                0x07,
+                vec![],
+                MaybeCommon::Uncommon(ShortVal::new(1)),
+            ))
+        };
+
+        let entries = vec![common_call(1, 5), common_call(2, 6)];
+        let compressed = compress_entries(&entries);
+        let common_tables = compressed.common_tables();
+        assert_eq!(common_tables.contracts(), vec![Contract::new([0x44; 32])]);
+
+        let Entry::Call(compressed_call) = compressed.entries()[1].clone() else {
+            panic!("Expected a call.");
+        };
+        assert_eq!(
+            compressed_call.contract(),
+            MaybeCommon::Common(Contract::new([0x44; 32]), 0)
+        );
+        assert_eq!(
+            roundtrip(&compressed.entries(), &common_tables),
+            compressed.entries()
+        );
+
+        // A single reference is not worth a table entry, so the contract is written in full.
+        let compressed = compress_entries(&[common_call(1, 5)]);
+        assert!(compressed.common_tables().is_empty());
+
+        let Entry::Call(compressed_call) = compressed.entries()[0].clone() else {
+            panic!("Expected a call.");
+        };
+        assert_eq!(
+            compressed_call.contract(),
+            MaybeCommon::Uncommon(Contract::new([0x44; 32]))
+        );
+        assert_eq!(roundtrip(&compressed.entries(), &()), compressed.entries());
+    }
+
+    #[test]
+    fn test_lift_entries() {
+        let liftup = Liftup::new(
+            Account::new(key(1)),
+            0,
+            Outpoint::new([0x11; 32], 1),
+            LongVal::new(100_000),
+        );
+        let liftdown = Liftdown::new(
+            Account::new(key(1)),
+            0,
+            hex::decode("0014751e76e8199196d454941c45d1b3a323f1433bd6").unwrap(),
+            LongVal::new(5_000_000_000),
+        );
+        let recharge = Recharge::new(
+            Account::new(key(2)),
+            0,
+            Outpoint::new([0x22; 32], 300),
+            LongVal::new(40_000),
+        );
+
+        let entries = vec![
+            Entry::Liftup(liftup),
+            transfer(1, 2, 1000),
+            Entry::Liftdown(liftdown.clone()),
+            Entry::Recharge(recharge),
+        ];
+
+        // CPE roundtrip
+        assert_eq!(roundtrip(&entries, &CommonTables::default()), entries);
+
+        // Entry type prefixes: b100, b101 and b110
+        let prefix = |entry: &Entry| -> Vec<bool> { entry.to_cpe().iter().take(3).collect() };
+        assert_eq!(prefix(&entries[0]), vec![true, false, false]);
+        assert_eq!(prefix(&entries[2]), vec![true, false, true]);
+        assert_eq!(prefix(&entries[3]), vec![true, true, false]);
+
+        // Reserved entry type b111 fails.
+        let bytes = [0b1110_0000u8; 8];
+        let mut reader = BitReader::new(&bytes);
+        assert!(Entry::from_cpe(&mut reader, &()).is_err());
+
+        // Compact accounts
+        let mut registry = AccountRegistry::new();
+        registry.register_entries(&entries);
+        assert_eq!(registry.len(), 2);
+
+        let compacted = registry.compact_entries(&entries);
+        let Entry::Recharge(compact_recharge) = compacted[3] else {
+            panic!("Expected a recharge.");
+        };
+        assert_eq!(compact_recharge.account(), Account::new_compact(key(2), 1));
+        assert_eq!(roundtrip(&compacted, &registry), compacted);
+
+        // Byte serialization
+        let bytes = liftup.serialize();
+        assert_eq!(bytes.len(), 1 + 32 + 4 + 32 + 4 + 8);
+        assert_eq!(bytes[0], 0x82);
+        assert_eq!(Liftup::from_bytes(&bytes).unwrap(), liftup);
+
+        let bytes = liftdown.serialize();
+        assert_eq!(bytes.len(), 1 + 32 + 4 + 1 + 22 + 8);
+        assert_eq!(bytes[0], 0x83);
+        assert_eq!(Liftdown::from_bytes(&bytes).unwrap(), liftdown);
+        assert_eq!(
+            Liftdown::from_bytes(&bytes[..40]),
+            Err(SerializeError::UnexpectedEof { offset: 40 })
+        );
+
+        let bytes = recharge.serialize();
+        assert_eq!(bytes[0], 0x84);
+        assert_eq!(Recharge::from_bytes(&bytes).unwrap(), recharge);
+        assert_eq!(
+            Liftup::from_bytes(&bytes),
+            Err(SerializeError::EntryTypeError {
+                offset: 0,
+                entry_type: 0x84
+            })
+        );
+
+        // Each entry type signs under its own tag.
+        let sighashes: Vec<[u8; 32]> = entries
+            .iter()
+            .map(|entry| entry.sighash([0x00; 32]))
+            .collect();
+        for (i, sighash) in sighashes.iter().enumerate() {
+            assert!(!sighashes[i + 1..].contains(sighash));
+        }
+        for entry in &entries {
+            assert!(entry.sign([0x01; 32], [0x00; 32]).is_ok());
+        }
+
+        // Lift entries pass through compression untouched.
+        let compressed = compress_entries(&entries);
+        assert_eq!(compressed.entries()[0], entries[0]);
+        assert_eq!(compressed.entries()[2], entries[2]);
+        assert_eq!(compressed.entries()[3], entries[3]);
+    }
+
+    #[test]
+    fn test_verify_entries() {
+        let entries = vec![
+            transfer(1, 2, 1000),
+            call(1, [0x33; 32], vec![vec![0x01]], 0),
+            Entry::Liftdown(Liftdown::new(
+                Account::new(key(1)),
+                0,
+                hex::decode("51").unwrap(),
+                LongVal::new(1000),
+            )),
+        ];
+
+        let prev_state_hash = [0xab; 32];
+
+        for entry in &entries {
+            assert_eq!(entry.signer(), Account::new(key(1)));
+
+            let signature = entry.sign([1; 32], prev_state_hash).unwrap();
+            assert!(entry.verify(signature, prev_state_hash).is_ok());
+
+            // Bound to the previous state
+            assert!(entry.verify(signature, [0x00; 32]).is_err());
+
+            // Signed by someone other than the signer
+            let signature = entry.sign([2; 32], prev_state_hash).unwrap();
+            assert!(entry.verify(signature, prev_state_hash).is_err());
+        }
+
+        // Tampered entry
+        let signature = entries[0].sign([1; 32], prev_state_hash).unwrap();
+        assert!(transfer(1, 2, 1001)
+            .verify(signature, prev_state_hash)
+            .is_err());
+
+        // Malformed signature
+        assert!(entries[0].verify([0x00; 64], prev_state_hash).is_err());
+    }
+
+    #[test]
+    fn test_transfer_long_amounts_and_fees() {
+        let mut transfer = Transfer::new_uncommon(
+            Account::new(key(1)),
+            0,
+            Account::new(key(2)),
+            LongVal::new(u64::MAX),
+        );
+        transfer.set_fee(LongVal::new(250));
+        assert_eq!(transfer.fee(), Some(LongVal::new(250)));
+
+        let entries = vec![Entry::Transfer(transfer), self::transfer(1, 2, 1000)];
+        assert_eq!(roundtrip(&entries, &()), entries);
+
+        // The fee is signed.
+        let mut without_fee = transfer;
+        without_fee.set_fee(LongVal::new(0));
+        assert_ne!(
+            Entry::Transfer(transfer).sighash([0x00; 32]),
+            Entry::Transfer(without_fee).sighash([0x00; 32])
+        );
+
+        // Compression keeps the fee.
+        let compressed = compress_entries(&[Entry::Transfer(transfer), Entry::Transfer(transfer)]);
+        let Entry::Transfer(compressed_transfer) = compressed.entries()[0] else {
+            panic!("Expected a transfer.");
+        };
+        assert_eq!(compressed_transfer.fee(), Some(LongVal::new(250)));
+        assert_eq!(
+            compressed_transfer.amount(),
+            MaybeCommon::Common(LongVal::new(u64::MAX), 0)
+        );
+    }
+
+    #[test]
+    fn test_entry_serialize() {
+        let mut with_fee = Transfer::new_uncommon(
+            Account::new(key(1)),
+            1,
+            Account::new(key(3)),
+            LongVal::new(1000),
+        );
+        with_fee.set_fee(LongVal::new(5));
+
+        let entries = vec![
+            transfer(1, 2, 1000),
+            Entry::Transfer(with_fee),
+            call(2, [0x33; 32], vec![vec![0xaa; 3]], 7),
+            Entry::Liftup(Liftup::new(
+                Account::new(key(3)),
+                0,
+                Outpoint::new([0x11; 32], 0),
+                LongVal::new(50_000),
+            )),
+            Entry::Liftdown(Liftdown::new(
+                Account::new(key(3)),
+                1,
+                hex::decode("51").unwrap(),
+                LongVal::new(40_000),
+            )),
+            Entry::Recharge(Recharge::new(
+                Account::new(key(4)),
+                0,
+                Outpoint::new([0x22; 32], 2),
+                LongVal::new(30_000),
+            )),
+        ];
+
+        // Each entry encodes exactly as its variant.
+        let type_bytes: Vec<u8> = entries.iter().map(|entry| entry.serialize()[0]).collect();
+        assert_eq!(type_bytes, vec![0x80, 0x80, 0x81, 0x82, 0x83, 0x84]);
+
+        for entry in &entries {
+            assert_eq!(Entry::from_bytes(&entry.serialize()).unwrap(), *entry);
+        }
+
+        let Entry::Call(call_entry) = &entries[2] else {
+            panic!("Expected a call.");
+        };
+        assert_eq!(entries[2].serialize(), call_entry.serialize());
+
+        // Back-to-back entries in a single stream
+        let mut stream = Vec::<u8>::new();
+        for entry in &entries {
+            entry.write_to(&mut stream).unwrap();
+        }
+
+        let mut reader = ByteReader::new(stream.as_slice());
+        for entry in &entries {
+            assert_eq!(Entry::read_from(&mut reader).unwrap(), *entry);
+        }
+        assert_eq!(reader.offset(), stream.len());
+        assert_eq!(reader.expect_end(), Ok(()));
+
+        // Unknown entry types
+        for entry_type in [0x05, 0x7f, 0x85, 0xff] {
+            let mut bytes = entries[0].serialize();
+            bytes[0] = entry_type;
+            assert_eq!(
+                Entry::from_bytes(&bytes),
+                Err(SerializeError::EntryTypeError {
+                    offset: 0,
+                    entry_type
+                })
+            );
+        }
+
+        // Reported at the offset of the entry within the stream
+        let mut bytes = entries[0].serialize();
+        bytes.push(0x09);
+        let mut reader = ByteReader::new(bytes.as_slice());
+        Entry::read_from(&mut reader).unwrap();
+        assert_eq!(
+            Entry::read_from(&mut reader),
+            Err(SerializeError::EntryTypeError {
+                offset: 78,
+                entry_type: 0x09
+            })
+        );
+
+        // Truncated input
+        let bytes = entries[4].serialize();
+        for len in 0..bytes.len() {
+            assert_eq!(
+                Entry::from_bytes(&bytes[..len]),
+                Err(SerializeError::UnexpectedEof { offset: len })
+            );
+        }
+
+        // A peeked byte counts as trailing.
+        let mut reader = ByteReader::new([0x01u8, 0x02].as_slice());
+        assert_eq!(reader.peek_u8(), Ok(0x01));
+        assert_eq!(reader.peek_u8(), Ok(0x01));
+        assert_eq!(reader.offset(), 0);
+        assert_eq!(reader.read_u8(), Ok(0x01));
+        assert_eq!(reader.peek_u8(), Ok(0x02));
+        assert_eq!(
+            reader.expect_end(),
+            Err(SerializeError::TrailingBytes { offset: 1 })
+        );
+    }
+
+    #[test]
+    fn test_entries_predating_nonces() {
+        let key_1: XOnlyPublicKey =
+            "b2d9fb51db445564f1d4e754f644597b11ff191d12c2a582fb598e509cd72421"
+                .parse()
+                .unwrap();
+        let key_2: XOnlyPublicKey =
+            "255ac1b59bafb50b4fead46fd8bf07884a9e23b6cd82a5e348a756b66973082e"
+                .parse()
+                .unwrap();
+
+        // Bytes written before entries carried nonces: a call, a liftup, a liftdown and a
+        // recharge.
+        let vectors = vec![
+            (
+                [
+                    "01255ac1b59bafb50b4fead46fd8bf07884a9e23b6cd82a5e348a756b66973082e",
+                    &"42".repeat(32),
+                    "0301020102f4010000",
+                ]
+                .concat(),
+                Entry::Call(Call::new_uncommon(
+                    Account::new(key_2),
+                    0,
+                    Contract::new([0x42; 32]),
+                    3,
+                    vec![vec![0x01, 0x02]],
+                    ShortVal::new(500),
+                )),
+            ),
+            (
+                [
+                    "02b2d9fb51db445564f1d4e754f644597b11ff191d12c2a582fb598e509cd72421",
+                    &"11".repeat(32),
+                    "01000000a086010000000000",
+                ]
+                .concat(),
+                Entry::Liftup(Liftup::new(
+                    Account::new(key_1),
+                    0,
+                    Outpoint::new([0x11; 32], 1),
+                    LongVal::new(100_000),
+                )),
+            ),
+            (
+                [
+                    "03255ac1b59bafb50b4fead46fd8bf07884a9e23b6cd82a5e348a756b66973082e",
+                    "225120",
+                    &"22".repeat(32),
+                    "8813000000000000",
+                ]
+                .concat(),
+                Entry::Liftdown(Liftdown::new(
+                    Account::new(key_2),
+                    0,
+                    hex::decode(format!("5120{}", "22".repeat(32))).unwrap(),
+                    LongVal::new(5_000),
+                )),
+            ),
+            (
+                [
+                    "04b2d9fb51db445564f1d4e754f644597b11ff191d12c2a582fb598e509cd72421",
+                    &"33".repeat(32),
+                    "00000000581b000000000000",
+                ]
+                .concat(),
+                Entry::Recharge(Recharge::new(
+                    Account::new(key_1),
+                    0,
+                    Outpoint::new([0x33; 32], 0),
+                    LongVal::new(7_000),
+                )),
+            ),
+        ];
+
+        for (bytes, entry) in &vectors {
+            let bytes = hex::decode(bytes).unwrap();
+
+            // Decode with a zero nonce.
+            assert_eq!(Entry::from_bytes(&bytes).unwrap(), *entry);
+
+            // Written back with the version bit and the zero nonce after the account
+            let mut versioned = bytes.clone();
+            versioned[0] |= 0x80;
+            versioned.splice(33..33, 0u32.to_le_bytes());
+            assert_eq!(entry.serialize(), versioned);
+            assert_eq!(Entry::from_bytes(&versioned).unwrap(), *entry);
+        }
+
+        // Transfer bytes written before transfers carried nonces
+        let transfer = Entry::Transfer(Transfer::new_uncommon(
+            Account::new(key_2),
+            0,
+            Account::new(key_1),
+            LongVal::new(1000),
+        ));
+        let bytes = hex::decode(
+            [
+                "00255ac1b59bafb50b4fead46fd8bf07884a9e23b6cd82a5e348a756b66973082e",
+                "b2d9fb51db445564f1d4e754f644597b11ff191d12c2a582fb598e509cd72421",
+                "e8030000",
+            ]
+            .concat(),
+        )
+        .unwrap();
+        assert_eq!(Entry::from_bytes(&bytes).unwrap(), transfer);
+
+        // Written back in the versioned layout
+        assert_eq!(transfer.serialize()[0], 0x80);
+        assert_eq!(Entry::from_bytes(&transfer.serialize()).unwrap(), transfer);
+
+        // A nonce sets the version bit and follows the account.
+        let liftup = Entry::Liftup(Liftup::new(
+            Account::new(key_1),
+            7,
+            Outpoint::new([0x11; 32], 1),
+            LongVal::new(100_000),
+        ));
+        let bytes = liftup.serialize();
+        assert_eq!(bytes[0], 0x82);
+        assert_eq!(bytes[33..37], 7u32.to_le_bytes());
+        assert_eq!(Entry::from_bytes(&bytes).unwrap(), liftup);
+    }
+}
@@ -0,0 +1,383 @@
+#[cfg(test)]
+mod ledger_tests {
+    use brollup::{
+        entry::{
+            call::Call, entry::Entry, liftdown::Liftdown, liftup::Liftup, recharge::Recharge,
+            transfer::Transfer,
+        },
+        ledger::{Ledger, LedgerError},
+        signature::schnorr::Sign,
+        taproot::P2TR,
+        txo::payload::{DecodedPayload, Payload},
+        valtype::{
+            account::Account,
+            contract::Contract,
+            outpoint::Outpoint,
+            value::{LongVal, ShortVal},
+        },
+    };
+    use musig2::secp256k1::{Secp256k1, SecretKey, XOnlyPublicKey};
+
+    fn key(seed: u8) -> XOnlyPublicKey {
+        let secret_key = SecretKey::from_slice(&[seed; 32]).unwrap();
+        secret_key.x_only_public_key(&Secp256k1::new()).0
+    }
+
+    fn transfer(from: u8, nonce: u32, to: u8, amount: u64) -> Entry {
+        Entry::Transfer(Transfer::new_uncommon(
+            Account::new(key(from)),
+            nonce,
+            Account::new(key(to)),
+            LongVal::new(amount),
+        ))
+    }
+
+    // Sign as the sender against the current ledger state and apply.
+    fn apply(ledger: &mut Ledger, entry: &Entry, seed: u8) -> Result<[u8; 32], LedgerError> {
+        let signature = entry.sign([seed; 32], ledger.state_hash()).unwrap();
+        ledger.apply(entry, signature)
+    }
+
+    fn payload_tapscript(entries: Vec<Entry>) -> Vec<u8> {
+        let payload = Payload::new(
+            vec![key(1), key(2)],
+            key(100),
+            vec![[0x11; 32], [0x22; 32]],
+            25,
+            10,
+            key(101),
+            [0xaa; 64],
+            [0xbb; 64],
+            entries,
+        );
+
+        payload.taproot().unwrap().tree().unwrap().leaves()[0].tap_script()
+    }
+
+    #[test]
+    fn test_ledger_transfers() {
+        let allocations = vec![(key(1), 10_000), (key(2), 500)];
+        let mut ledger = Ledger::genesis(&allocations).unwrap();
+        let genesis_hash = ledger.state_hash();
+
+        // Genesis commits to the allocations.
+        assert_ne!(
+            Ledger::genesis(&[(key(1), 10_000)]).unwrap().state_hash(),
+            genesis_hash
+        );
+
+        let state_hash = apply(&mut ledger, &transfer(1, 0, 3, 4000), 1).unwrap();
+        assert_ne!(state_hash, genesis_hash);
+        assert_eq!(ledger.state_hash(), state_hash);
+        assert_eq!(ledger.balance(&key(1)), Some(6000));
+        assert_eq!(ledger.balance(&key(3)), Some(4000));
+
+        // Self-transfer
+        apply(&mut ledger, &transfer(2, 0, 2, 500), 2).unwrap();
+        assert_eq!(ledger.balance(&key(2)), Some(500));
+
+        // Rejections leave the state untouched.
+        let before = ledger.clone();
+
+        assert_eq!(
+            apply(&mut ledger, &transfer(1, 1, 2, 6001), 1),
+            Err(LedgerError::InsufficientBalance {
+                balance: 6000,
+                amount: 6001
+            })
+        );
+        assert_eq!(
+            apply(&mut ledger, &transfer(4, 0, 1, 0), 4),
+            Err(LedgerError::UnknownSender(key(4)))
+        );
+
+        // Signed by the wrong key
+        assert_eq!(
+            apply(&mut ledger, &transfer(1, 1, 2, 100), 2),
+            Err(LedgerError::InvalidSignature)
+        );
+
+        // Signed against a stale state
+        let entry = transfer(1, 1, 2, 100);
+        let stale_signature = entry.sign([1; 32], genesis_hash).unwrap();
+        assert_eq!(
+            ledger.apply(&entry, stale_signature),
+            Err(LedgerError::InvalidSignature)
+        );
+
+        assert_eq!(ledger, before);
+
+        // Overflow
+        let mut ledger = Ledger::genesis(&[(key(1), 1), (key(2), u64::MAX)]).unwrap();
+        assert_eq!(
+            apply(&mut ledger, &transfer(1, 0, 2, 1), 1),
+            Err(LedgerError::BalanceOverflow)
+        );
+        assert_eq!(
+            Ledger::genesis(&[(key(1), u64::MAX), (key(1), 1)]),
+            Err(LedgerError::BalanceOverflow)
+        );
+    }
+
+    #[test]
+    fn test_ledger_lifts() {
+        let mut ledger = Ledger::genesis(&[(key(1), 10_000)]).unwrap();
+
+        // Liftups credit the account, which may hold no funds yet.
+        let liftup = Entry::Liftup(Liftup::new(
+            Account::new(key(2)),
+            0,
+            Outpoint::new([0x11; 32], 0),
+            LongVal::new(50_000),
+        ));
+        apply(&mut ledger, &liftup, 2).unwrap();
+        assert_eq!(ledger.balance(&key(2)), Some(50_000));
+        assert_eq!(ledger.next_nonce(&key(2)), 1);
+
+        let liftup = Entry::Liftup(Liftup::new(
+            Account::new(key(1)),
+            0,
+            Outpoint::new([0x11; 32], 1),
+            LongVal::new(5_000),
+        ));
+        apply(&mut ledger, &liftup, 1).unwrap();
+        assert_eq!(ledger.balance(&key(1)), Some(15_000));
+
+        // Liftdowns debit the account.
+        let spk = hex::decode(format!("5120{}", "22".repeat(32))).unwrap();
+        let liftdown = Entry::Liftdown(Liftdown::new(
+            Account::new(key(2)),
+            1,
+            spk.clone(),
+            LongVal::new(20_000),
+        ));
+        apply(&mut ledger, &liftdown, 2).unwrap();
+        assert_eq!(ledger.balance(&key(2)), Some(30_000));
+        assert_eq!(ledger.next_nonce(&key(2)), 2);
+        assert_eq!(ledger.balance(&key(1)), Some(15_000));
+        assert_eq!(ledger.fees(), 0);
+
+        // Rejections leave the state untouched.
+        let before = ledger.clone();
+
+        let liftdown = Entry::Liftdown(Liftdown::new(
+            Account::new(key(2)),
+            2,
+            spk.clone(),
+            LongVal::new(30_001),
+        ));
+        assert_eq!(
+            apply(&mut ledger, &liftdown, 2),
+            Err(LedgerError::InsufficientBalance {
+                balance: 30_000,
+                amount: 30_001
+            })
+        );
+
+        let liftdown =
+            Entry::Liftdown(Liftdown::new(Account::new(key(3)), 0, spk, LongVal::new(1)));
+        assert_eq!(
+            apply(&mut ledger, &liftdown, 3),
+            Err(LedgerError::UnknownSender(key(3)))
+        );
+
+        let liftup = Entry::Liftup(Liftup::new(
+            Account::new(key(1)),
+            1,
+            Outpoint::new([0x11; 32], 2),
+            LongVal::new(u64::MAX),
+        ));
+        assert_eq!(
+            apply(&mut ledger, &liftup, 1),
+            Err(LedgerError::BalanceOverflow)
+        );
+
+        // Calls and recharges are not executed by the ledger.
+        let call = Entry::Call(Call::new_uncommon(
+            Account::new(key(1)),
+            1,
+            Contract::new([0x33; 32]),
+            0,
+            vec![],
+            ShortVal::new(0),
+        ));
+        assert_eq!(
+            apply(&mut ledger, &call, 1),
+            Err(LedgerError::UnsupportedEntry)
+        );
+
+        let recharge = Entry::Recharge(Recharge::new(
+            Account::new(key(1)),
+            1,
+            Outpoint::new([0x44; 32], 0),
+            LongVal::new(1_000),
+        ));
+        assert_eq!(
+            apply(&mut ledger, &recharge, 1),
+            Err(LedgerError::UnsupportedEntry)
+        );
+
+        assert_eq!(ledger, before);
+    }
+
+    #[test]
+    fn test_ledger_replay() {
+        let allocations = vec![(key(1), 10_000)];
+        let mut ledger = Ledger::genesis(&allocations).unwrap();
+
+        let entries_1 = vec![transfer(1, 0, 2, 3000), transfer(2, 0, 3, 1000)];
+        let entries_2 = vec![transfer(3, 0, 1, 250)];
+
+        for entry in &entries_1 {
+            let seed = match entry {
+                Entry::Transfer(transfer) if transfer.from().key() == key(1) => 1,
+                _ => 2,
+            };
+            apply(&mut ledger, entry, seed).unwrap();
+        }
+        apply(&mut ledger, &entries_2[0], 3).unwrap();
+
+        let tap_scripts = [payload_tapscript(entries_1), payload_tapscript(entries_2)];
+        let payloads: Vec<DecodedPayload> = tap_scripts
+            .iter()
+            .map(|tap_script| Payload::from_tapscript(tap_script, &()).unwrap())
+            .collect();
+
+        let replayed = Ledger::replay(&allocations, &payloads).unwrap();
+        assert_eq!(replayed.state_hash(), ledger.state_hash());
+        assert_eq!(replayed, ledger);
+        assert_eq!(replayed.balance(&key(1)), Some(7250));
+        assert_eq!(replayed.balance(&key(2)), Some(2000));
+        assert_eq!(replayed.balance(&key(3)), Some(750));
+
+        // Replaying out of order fails.
+        let reversed: Vec<DecodedPayload> = tap_scripts
+            .iter()
+            .rev()
+            .map(|tap_script| Payload::from_tapscript(tap_script, &()).unwrap())
+            .collect();
+        assert_eq!(
+            Ledger::replay(&allocations, &reversed),
+            Err(LedgerError::UnknownSender(key(3)))
+        );
+
+        // A payload failing part way through leaves the ledger untouched.
+        let tap_script = payload_tapscript(vec![transfer(1, 1, 2, 100), transfer(3, 1, 2, 5000)]);
+        let payload = Payload::from_tapscript(&tap_script, &()).unwrap();
+        let before = replayed.clone();
+        let mut replayed = replayed;
+        assert_eq!(
+            replayed.apply_payload(&payload),
+            Err(LedgerError::InsufficientBalance {
+                balance: 750,
+                amount: 5000
+            })
+        );
+        assert_eq!(replayed, before);
+    }
+
+    #[test]
+    fn test_ledger_nonces() {
+        let mut ledger = Ledger::genesis(&[(key(1), 10_000)]).unwrap();
+        assert_eq!(ledger.next_nonce(&key(1)), 0);
+
+        let entry = transfer(1, 0, 2, 1000);
+        let signature = entry.sign([1; 32], ledger.state_hash()).unwrap();
+        ledger.apply(&entry, signature).unwrap();
+        assert_eq!(ledger.next_nonce(&key(1)), 1);
+        assert_eq!(ledger.next_nonce(&key(2)), 0);
+
+        // Replaying the same entry fails, even when re-signed against the current state.
+        let before = ledger.clone();
+        assert_eq!(
+            apply(&mut ledger, &entry, 1),
+            Err(LedgerError::InvalidNonce {
+                expected: 1,
+                nonce: 0
+            })
+        );
+
+        // Nonces cannot be skipped.
+        assert_eq!(
+            apply(&mut ledger, &transfer(1, 2, 2, 1000), 1),
+            Err(LedgerError::InvalidNonce {
+                expected: 1,
+                nonce: 2
+            })
+        );
+        assert_eq!(ledger, before);
+
+        // Failed entries do not consume the nonce.
+        assert!(apply(&mut ledger, &transfer(1, 1, 2, 1_000_000), 1).is_err());
+        assert_eq!(ledger.next_nonce(&key(1)), 1);
+
+        apply(&mut ledger, &transfer(1, 1, 2, 1000), 1).unwrap();
+        apply(&mut ledger, &transfer(2, 0, 1, 500), 2).unwrap();
+        assert_eq!(ledger.next_nonce(&key(1)), 2);
+        assert_eq!(ledger.next_nonce(&key(2)), 1);
+        assert_eq!(ledger.balance(&key(2)), Some(1500));
+
+        // The nonce is part of the signed message.
+        let signature = transfer(1, 2, 2, 100)
+            .sign([1; 32], ledger.state_hash())
+            .unwrap();
+        assert_eq!(
+            ledger.apply(&transfer(1, 3, 2, 100), signature),
+            Err(LedgerError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn test_ledger_fees() {
+        let mut ledger = Ledger::genesis(&[(key(1), 10_000_000_000)]).unwrap();
+
+        // Amounts beyond 4 bytes
+        apply(&mut ledger, &transfer(1, 0, 2, 6_000_000_000), 1).unwrap();
+        assert_eq!(ledger.balance(&key(2)), Some(6_000_000_000));
+
+        // The sender pays the fee on top of the amount.
+        let mut with_fee = Transfer::new_uncommon(
+            Account::new(key(2)),
+            0,
+            Account::new(key(3)),
+            LongVal::new(1_000_000),
+        );
+        with_fee.set_fee(LongVal::new(500));
+
+        apply(&mut ledger, &Entry::Transfer(with_fee), 2).unwrap();
+        assert_eq!(ledger.balance(&key(2)), Some(5_998_999_500));
+        assert_eq!(ledger.balance(&key(3)), Some(1_000_000));
+        assert_eq!(ledger.fees(), 500);
+
+        // Self-transfers pay only the fee.
+        let mut self_transfer = Transfer::new_uncommon(
+            Account::new(key(3)),
+            0,
+            Account::new(key(3)),
+            LongVal::new(500_000),
+        );
+        self_transfer.set_fee(LongVal::new(100));
+
+        apply(&mut ledger, &Entry::Transfer(self_transfer), 3).unwrap();
+        assert_eq!(ledger.balance(&key(3)), Some(999_900));
+        assert_eq!(ledger.fees(), 600);
+
+        // The balance must cover both.
+        let mut overdraft = Transfer::new_uncommon(
+            Account::new(key(3)),
+            1,
+            Account::new(key(1)),
+            LongVal::new(999_900),
+        );
+        overdraft.set_fee(LongVal::new(1));
+
+        assert_eq!(
+            apply(&mut ledger, &Entry::Transfer(overdraft), 3),
+            Err(LedgerError::InsufficientBalance {
+                balance: 999_900,
+                amount: 999_901
+            })
+        );
+        assert_eq!(ledger.fees(), 600);
+    }
+}
@@ -0,0 +1,193 @@
+#[cfg(test)]
+mod mempool_tests {
+    use brollup::{
+        entry::{compressor::CommonTables, entry::Entry, transfer::Transfer},
+        ledger::{Ledger, LedgerError},
+        mempool::{Mempool, MempoolError},
+        serialization::{cpe::CompactPayloadEncoding, push::Push},
+        signature::schnorr::Sign,
+        taproot::P2TR,
+        txo::payload::Payload,
+        valtype::{account::Account, value::LongVal},
+    };
+    use musig2::secp256k1::{Secp256k1, SecretKey, XOnlyPublicKey};
+
+    fn key(seed: u8) -> XOnlyPublicKey {
+        let secret_key = SecretKey::from_slice(&[seed; 32]).unwrap();
+        secret_key.x_only_public_key(&Secp256k1::new()).0
+    }
+
+    fn transfer(from: u8, nonce: u32, to: u8, amount: u64) -> Entry {
+        Entry::Transfer(Transfer::new_uncommon(
+            Account::new(key(from)),
+            nonce,
+            Account::new(key(to)),
+            LongVal::new(amount),
+        ))
+    }
+
+    // Sign as the sender against the pending state and insert.
+    fn insert(mempool: &mut Mempool, entry: &Entry, seed: u8) -> Result<[u8; 32], MempoolError> {
+        let signature = entry.sign([seed; 32], mempool.state_hash()).unwrap();
+        mempool.insert(entry.clone(), signature)
+    }
+
+    fn payload_tapscript(entries: Vec<Entry>) -> Vec<u8> {
+        let payload = Payload::new(
+            vec![key(1), key(2)],
+            key(100),
+            vec![[0x11; 32], [0x22; 32]],
+            25,
+            10,
+            key(101),
+            [0xaa; 64],
+            [0xbb; 64],
+            entries,
+        );
+
+        payload.taproot().unwrap().tree().unwrap().leaves()[0].tap_script()
+    }
+
+    #[test]
+    fn test_mempool_insert() {
+        let ledger = Ledger::genesis(&[(key(1), 10_000), (key(2), 500)]).unwrap();
+        let mut mempool = Mempool::new(&ledger);
+        assert!(mempool.is_empty());
+        assert_eq!(mempool.state_hash(), ledger.state_hash());
+
+        let entry = transfer(1, 0, 2, 4000);
+        let signature = entry.sign([1; 32], mempool.state_hash()).unwrap();
+        let state_hash = mempool.insert(entry.clone(), signature).unwrap();
+        assert_eq!(mempool.state_hash(), state_hash);
+        assert_eq!(mempool.pending().balance(&key(2)), Some(4500));
+
+        // Validated against the pending state, not the confirmed one.
+        insert(&mut mempool, &transfer(2, 0, 3, 4500), 2).unwrap();
+        assert_eq!(mempool.len(), 2);
+
+        // Duplicates and conflicting nonces are rejected.
+        assert_eq!(
+            mempool.insert(entry, signature),
+            Err(MempoolError::DuplicateEntry)
+        );
+        assert_eq!(
+            insert(&mut mempool, &transfer(1, 0, 3, 1), 1),
+            Err(MempoolError::ConflictingEntry { nonce: 0 })
+        );
+
+        // Invalid entries leave the pool untouched.
+        let state_hash = mempool.state_hash();
+        assert_eq!(
+            insert(&mut mempool, &transfer(1, 1, 3, 6001), 1),
+            Err(MempoolError::LedgerError(
+                LedgerError::InsufficientBalance {
+                    balance: 6000,
+                    amount: 6001
+                }
+            ))
+        );
+        assert_eq!(
+            insert(&mut mempool, &transfer(1, 2, 3, 1), 1),
+            Err(MempoolError::LedgerError(LedgerError::InvalidNonce {
+                expected: 1,
+                nonce: 2
+            }))
+        );
+        assert_eq!(
+            insert(&mut mempool, &transfer(1, 1, 3, 1), 2),
+            Err(MempoolError::LedgerError(LedgerError::InvalidSignature))
+        );
+        assert_eq!(mempool.state_hash(), state_hash);
+        assert_eq!(
+            mempool.entries(),
+            vec![transfer(1, 0, 2, 4000), transfer(2, 0, 3, 4500)]
+        );
+    }
+
+    #[test]
+    fn test_mempool_batch() {
+        let ledger = Ledger::genesis(&[(key(1), 1_000_000)]).unwrap();
+        let mut mempool = Mempool::new(&ledger);
+
+        for nonce in 0..40 {
+            insert(&mut mempool, &transfer(1, nonce, 2, 1000), 1).unwrap();
+        }
+
+        let entries_bits =
+            |entries: &[Entry]| -> usize { entries.iter().map(|entry| entry.to_cpe().len()).sum() };
+
+        for target_len in [1000, 1500, 2500] {
+            let batch = mempool.batch(target_len).unwrap();
+            assert!(!batch.is_empty() && batch.len() < mempool.len());
+            assert_eq!(batch[..], mempool.entries()[..batch.len()]);
+
+            // The batch fits, one more entry does not.
+            let bits = entries_bits(&batch);
+            assert!(Payload::data_push_len(bits) <= target_len);
+            assert!(
+                Payload::data_push_len(bits + entries_bits(&mempool.entries()[batch.len()..][..1]))
+                    > target_len
+            );
+
+            // The payload carries the data in 520-byte pushes of exactly that size.
+            let tap_script = payload_tapscript(batch.clone());
+            assert_eq!(
+                tap_script.len() - payload_tapscript(vec![]).len(),
+                Payload::data_push_len(bits) - Payload::data_push_len(0)
+            );
+
+            let decoded = Payload::from_tapscript(&tap_script, &()).unwrap();
+            assert_eq!(decoded.entries(), batch);
+        }
+
+        // Everything fits.
+        assert_eq!(mempool.batch(100_000).unwrap(), mempool.entries());
+
+        // Not even the header fits.
+        assert_eq!(mempool.batch(160), Err(MempoolError::BatchTooLarge));
+
+        // Empty common tables take the first bits of the data.
+        let table_bits = CommonTables::default().to_cpe().len();
+        for data_len in [166, 255, 256, 519, 520, 521, 1040, 1041, 1300] {
+            let entries_bits = (data_len - 164) * 8 - table_bits;
+            assert_eq!(
+                Payload::data_push_len(entries_bits),
+                vec![0xff; data_len].as_multi_pushdata_push().len()
+            );
+        }
+    }
+
+    #[test]
+    fn test_mempool_sync() {
+        let mut ledger = Ledger::genesis(&[(key(1), 10_000), (key(2), 1000)]).unwrap();
+        let mut mempool = Mempool::new(&ledger);
+
+        insert(&mut mempool, &transfer(1, 0, 2, 1000), 1).unwrap();
+        insert(&mut mempool, &transfer(1, 1, 2, 1000), 1).unwrap();
+        insert(&mut mempool, &transfer(2, 0, 3, 1500), 2).unwrap();
+
+        // Confirm the first batch.
+        let target_len = Payload::data_push_len(mempool.entries()[0].to_cpe().len());
+        let batch = mempool.batch(target_len).unwrap();
+        assert_eq!(batch.len(), 1);
+
+        let tap_script = payload_tapscript(batch);
+        let payload = Payload::from_tapscript(&tap_script, &()).unwrap();
+        ledger.apply_payload(&payload).unwrap();
+
+        let pending = mempool.pending().clone();
+        assert!(mempool.sync(&ledger).is_empty());
+        assert_eq!(
+            mempool.entries(),
+            vec![transfer(1, 1, 2, 1000), transfer(2, 0, 3, 1500)]
+        );
+        assert_eq!(mempool.pending(), &pending);
+
+        // A confirmed state the pool was not built on evicts the entries that no longer apply.
+        let other = Ledger::genesis(&[(key(1), 10_000), (key(2), 1000)]).unwrap();
+        let evicted = mempool.sync(&other);
+        assert_eq!(evicted.len(), 2);
+        assert!(mempool.is_empty());
+        assert_eq!(mempool.state_hash(), other.state_hash());
+    }
+}
@@ -0,0 +1,256 @@
+#[cfg(test)]
+mod registry_tests {
+    use brollup::{
+        entry::{compressor::compress_entries, entry::Entry, transfer::Transfer},
+        registry::{
+            account::AccountRegistry,
+            context::RegistryContext,
+            contract::{derive_contract_id, ContractRegistry},
+        },
+        serialization::{
+            bits::BitReader,
+            cpe::{CompactPayloadDecoding, CompactPayloadEncoding},
+            serialize::{Serialize, SerializeError},
+        },
+        taproot::P2TR,
+        txo::payload::Payload,
+        valtype::{account::Account, contract::Contract, value::LongVal},
+    };
+    use musig2::secp256k1::{Secp256k1, SecretKey, XOnlyPublicKey};
+
+    fn key(seed: u8) -> XOnlyPublicKey {
+        let secret_key = SecretKey::from_slice(&[seed; 32]).unwrap();
+        secret_key.x_only_public_key(&Secp256k1::new()).0
+    }
+
+    fn transfer(from: u8, to: u8, amount: u64) -> Entry {
+        Entry::Transfer(Transfer::new_uncommon(
+            Account::new(key(from)),
+            0,
+            Account::new(key(to)),
+            LongVal::new(amount),
+        ))
+    }
+
+    fn payload(entries: Vec<Entry>) -> Payload {
+        Payload::new(
+            vec![key(1), key(2)],
+            key(100),
+            vec![[0x11; 32], [0x22; 32]],
+            25,
+            10,
+            key(101),
+            [0xaa; 64],
+            [0xbb; 64],
+            entries,
+        )
+    }
+
+    fn payload_tapscript(entries: Vec<Entry>) -> Vec<u8> {
+        payload(entries).taproot().unwrap().tree().unwrap().leaves()[0].tap_script()
+    }
+
+    #[test]
+    fn test_account_registry() {
+        let mut registry = AccountRegistry::new();
+
+        assert_eq!(registry.register(key(1)), 0);
+        assert_eq!(registry.register(key(2)), 1);
+        assert_eq!(registry.register(key(1)), 0);
+        assert_eq!(registry.len(), 2);
+
+        assert_eq!(registry.index_of(&key(2)), Some(1));
+        assert_eq!(registry.index_of(&key(3)), None);
+        assert_eq!(registry.key_at(1), Some(key(2)));
+        assert_eq!(registry.key_at(2), None);
+
+        assert_eq!(
+            registry.compact(Account::new(key(2))),
+            Account::new_compact(key(2), 1)
+        );
+        assert_eq!(registry.compact(Account::new(key(3))), Account::new(key(3)));
+
+        // Senders and recipients are registered in order of appearance.
+        registry.register_entries(&[transfer(3, 4, 100), transfer(4, 1, 100)]);
+        assert_eq!(registry.index_of(&key(3)), Some(2));
+        assert_eq!(registry.index_of(&key(4)), Some(3));
+        assert_eq!(registry.len(), 4);
+
+        // Compact entries
+        let compacted = registry.compact_entries(&[transfer(3, 5, 100)]);
+        let Entry::Transfer(compacted) = compacted[0] else {
+            panic!("Expected a transfer.");
+        };
+        assert_eq!(compacted.from(), Account::new_compact(key(3), 2));
+        assert_eq!(compacted.to().value(), Account::new(key(5)));
+
+        let compacted = registry.compact_entries(&[transfer(5, 4, 100)]);
+        let Entry::Transfer(compacted) = compacted[0] else {
+            panic!("Expected a transfer.");
+        };
+        assert_eq!(compacted.from(), Account::new(key(5)));
+        assert_eq!(compacted.to().value(), Account::new_compact(key(4), 3));
+
+        // Persistence
+        let bytes = registry.serialize();
+        assert_eq!(bytes.len(), 4 + 4 * 32);
+        assert_eq!(AccountRegistry::from_bytes(&bytes).unwrap(), registry);
+
+        let mut duplicate = bytes.clone();
+        duplicate[0] = 5;
+        duplicate.extend(key(2).serialize());
+        assert_eq!(
+            AccountRegistry::from_bytes(&duplicate),
+            Err(SerializeError::DuplicateKey { offset: 132 })
+        );
+
+        assert_eq!(
+            AccountRegistry::from_bytes(&bytes[..100]),
+            Err(SerializeError::UnexpectedEof { offset: 100 })
+        );
+    }
+
+    #[test]
+    fn test_account_registry_rebuild() {
+        let mut registry = AccountRegistry::new();
+
+        // First payload introduces keys 1, 2 and 3.
+        let entries_1 = vec![transfer(1, 2, 1000), transfer(3, 1, 2000)];
+        let tap_script_1 = payload_tapscript(registry.compact_entries(&entries_1));
+        registry.register_entries(&entries_1);
+
+        // Second payload refers to known keys by index and introduces key 4.
+        let entries_2 = registry.compact_entries(&[transfer(2, 4, 500), transfer(3, 1, 700)]);
+        let tap_script_2 = payload_tapscript(entries_2.clone());
+        registry.register_entries(&entries_2);
+
+        let Entry::Transfer(transfer_2) = entries_2[0] else {
+            panic!("Expected a transfer.");
+        };
+        assert_eq!(transfer_2.from(), Account::new_compact(key(2), 1));
+
+        // The second payload can only be decoded against the registry.
+        assert!(Payload::from_tapscript(&tap_script_2, &AccountRegistry::new()).is_err());
+
+        let rebuilt =
+            AccountRegistry::from_tapscripts(&[tap_script_1.clone(), tap_script_2.clone()])
+                .unwrap();
+
+        assert_eq!(rebuilt, registry);
+        assert_eq!(rebuilt.len(), 4);
+        assert_eq!(rebuilt.key_at(3), Some(key(4)));
+
+        let decoded = Payload::from_tapscript(&tap_script_2, &rebuilt).unwrap();
+        assert_eq!(decoded.entries(), entries_2);
+
+        // Third payload is compressed: its common tables refer to known keys by index.
+        let entries_3 = registry.compact_entries(&[
+            transfer(1, 4, 300),
+            transfer(2, 4, 300),
+            transfer(3, 5, 300),
+        ]);
+        let compressed = compress_entries(&entries_3);
+        assert_eq!(
+            compressed.common_tables().accounts(),
+            vec![Account::new_compact(key(4), 3)]
+        );
+
+        let mut payload_3 = payload(entries_3.clone());
+        payload_3.compress();
+        let tap_script_3 = payload_3.taproot().unwrap().tree().unwrap().leaves()[0].tap_script();
+        registry.register_entries(&entries_3);
+
+        let rebuilt = AccountRegistry::from_tapscripts(&[
+            tap_script_1.clone(),
+            tap_script_2.clone(),
+            tap_script_3.clone(),
+        ])
+        .unwrap();
+        assert_eq!(rebuilt, registry);
+        assert_eq!(rebuilt.key_at(4), Some(key(5)));
+
+        let decoded = Payload::from_tapscript(&tap_script_3, &rebuilt).unwrap();
+        assert_eq!(decoded.entries(), compressed.entries());
+
+        // Replaying out of order fails.
+        assert!(AccountRegistry::from_tapscripts(&[tap_script_2, tap_script_1]).is_err());
+    }
+
+    #[test]
+    fn test_contract_registry() {
+        let deployer: XOnlyPublicKey =
+            "b2d9fb51db445564f1d4e754f644597b11ff191d12c2a582fb598e509cd72421"
+                .parse()
+                .unwrap();
+        let program = hex::decode("51ac87").unwrap();
+
+        let contract_id = derive_contract_id(deployer, 0, &program);
+        assert_eq!(
+            hex::encode(contract_id),
+            "19e0879fd7189e5a8d4f30a94b7e15e10228f532a35e7c74d1401e9ce58a31fe"
+        );
+
+        // The nonce and the program both change the ID.
+        assert_ne!(derive_contract_id(deployer, 1, &program), contract_id);
+        assert_ne!(derive_contract_id(deployer, 0, &program[..2]), contract_id);
+
+        // Indexes are assigned in deployment order.
+        let mut registry = ContractRegistry::new();
+
+        let contract_0 = registry.deploy(deployer, 0, &program).unwrap();
+        let contract_1 = registry.deploy(deployer, 1, &program).unwrap();
+
+        assert_eq!(contract_0, Contract::new_compact(contract_id, 0));
+        assert_eq!(contract_1.contract_index(), Some(1));
+        assert_eq!(registry.deploy(deployer, 0, &program), None);
+        assert_eq!(registry.len(), 2);
+
+        assert_eq!(registry.contract_id_at(0), Some(contract_id));
+        assert_eq!(registry.index_of(&contract_1.contract_id()), Some(1));
+        assert_eq!(registry.compact(Contract::new(contract_id)), contract_0);
+        assert_eq!(
+            registry.compact(Contract::new([0x00; 32])),
+            Contract::new([0x00; 32])
+        );
+
+        // Compact contracts resolve through the registry during CPE decoding.
+        let bytes = contract_1.to_cpe().to_bytes();
+
+        let mut reader = BitReader::new(&bytes);
+        assert_eq!(
+            Contract::from_cpe(&mut reader, &registry).unwrap(),
+            contract_1
+        );
+
+        let mut reader = BitReader::new(&bytes);
+        assert!(Contract::from_cpe(&mut reader, &ContractRegistry::new()).is_err());
+
+        // Combined with accounts
+        let mut account_registry = AccountRegistry::new();
+        account_registry.register(deployer);
+        let context = RegistryContext::new(account_registry, registry.clone());
+
+        let mut reader = BitReader::new(&bytes);
+        assert_eq!(
+            Contract::from_cpe(&mut reader, &context).unwrap(),
+            contract_1
+        );
+
+        let account = Account::new_compact(deployer, 0);
+        let bytes = account.to_cpe().to_bytes();
+        let mut reader = BitReader::new(&bytes);
+        assert_eq!(Account::from_cpe(&mut reader, &context).unwrap(), account);
+
+        // Persistence
+        let bytes = registry.serialize();
+        assert_eq!(ContractRegistry::from_bytes(&bytes).unwrap(), registry);
+
+        let mut duplicate = bytes.clone();
+        duplicate[0] = 3;
+        duplicate.extend(contract_id);
+        assert_eq!(
+            ContractRegistry::from_bytes(&duplicate),
+            Err(SerializeError::DuplicateContractId { offset: 68 })
+        );
+    }
+}
@@ -0,0 +1,162 @@
+#[cfg(test)]
+mod script_tests {
+    use brollup::script::{
+        asm::{assemble, disassemble, ScriptError},
+        builder::ScriptBuilder,
+        opcode::{OP_CHECKSIG, OP_IF},
+    };
+    use brollup::serialization::csv::CSVFlag;
+    use musig2::secp256k1::XOnlyPublicKey;
+
+    #[test]
+    fn test_disassemble() -> Result<(), ScriptError> {
+        // Lift exit path
+        let script = hex::decode(
+            "0350cd00b27520b2d9fb51db445564f1d4e754f644597b11ff191d12c2a582fb598e509cd72421ac",
+        )
+        .unwrap();
+
+        let asm = disassemble(&script)?;
+        let asm_expected = "<50cd00> OP_CSV OP_DROP <b2d9fb51db445564f1d4e754f644597b11ff191d12c2a582fb598e509cd72421> OP_CHECKSIG";
+
+        assert_eq!(asm, asm_expected);
+        assert_eq!(assemble(&asm)?, script);
+
+        // Minimal pushes
+        let script = hex::decode("00514f5560").unwrap();
+        let asm = disassemble(&script)?;
+
+        assert_eq!(asm, "OP_0 OP_1 OP_1NEGATE OP_5 OP_16");
+        assert_eq!(assemble(&asm)?, script);
+
+        // Hashlock
+        let script = hex::decode("a914aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa88").unwrap();
+        let asm = disassemble(&script)?;
+
+        assert_eq!(
+            asm,
+            "OP_HASH160 <aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa> OP_EQUALVERIFY"
+        );
+        assert_eq!(assemble(&asm)?, script);
+
+        // Undefined opcodes
+        assert_eq!(
+            disassemble(&[0xba, 0xbb, 0xfe, 0xff])?,
+            "OP_CHECKSIGADD OP_SUCCESS187 OP_SUCCESS254 OP_INVALIDOPCODE"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_disassemble_non_minimal() -> Result<(), ScriptError> {
+        // Single byte pushed directly instead of OP_5
+        let script = hex::decode("0105").unwrap();
+        let asm = disassemble(&script)?;
+
+        assert_eq!(asm, "OP_PUSHBYTES_1 <05>");
+        assert_eq!(assemble(&asm)?, script);
+
+        // OP_PUSHDATA1 for a push that fits a direct push
+        let script = hex::decode("4c02aabb").unwrap();
+        let asm = disassemble(&script)?;
+
+        assert_eq!(asm, "OP_PUSHDATA1 <aabb>");
+        assert_eq!(assemble(&asm)?, script);
+
+        // OP_PUSHDATA2 with an empty push
+        let script = hex::decode("4d0000").unwrap();
+        let asm = disassemble(&script)?;
+
+        assert_eq!(asm, "OP_PUSHDATA2 <>");
+        assert_eq!(assemble(&asm)?, script);
+
+        // Truncated pushes
+        assert_eq!(
+            disassemble(&hex::decode("20aabb").unwrap()),
+            Err(ScriptError::UnexpectedEndOfScript)
+        );
+        assert_eq!(
+            disassemble(&hex::decode("4d01").unwrap()),
+            Err(ScriptError::UnexpectedEndOfScript)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_assemble() -> Result<(), ScriptError> {
+        // Aliases
+        assert_eq!(
+            assemble("OP_TRUE OP_FALSE OP_CHECKSEQUENCEVERIFY OP_CHECKLOCKTIMEVERIFY OP_NOP2")?,
+            hex::decode("5100b2b1b1").unwrap()
+        );
+
+        // Data is pushed minimally
+        assert_eq!(
+            assemble("<> <10> <11> <81>")?,
+            hex::decode("006001114f").unwrap()
+        );
+
+        assert_eq!(
+            assemble("OP_FOO"),
+            Err(ScriptError::UnknownOpcode("OP_FOO".to_string()))
+        );
+        assert_eq!(
+            assemble("<zz>"),
+            Err(ScriptError::InvalidHex("<zz>".to_string()))
+        );
+        assert_eq!(
+            assemble("OP_PUSHBYTES_2 <aa>"),
+            Err(ScriptError::PushLengthError("OP_PUSHBYTES_2".to_string()))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_script_builder() {
+        // Minimal number pushes
+        let script = ScriptBuilder::new()
+            .push_int(0)
+            .push_int(-1)
+            .push_int(16)
+            .push_int(17)
+            .push_int(127)
+            .push_int(128)
+            .push_int(-128)
+            .push_int(255)
+            .push_int(32768)
+            .into_script();
+
+        assert_eq!(
+            script,
+            hex::decode("004f600111017f02800002808002ff0003008000").unwrap()
+        );
+
+        // Block-based CSV matches the CSV flag encoding
+        let days = ScriptBuilder::new().push_csv(CSVFlag::Days(1)).into_script();
+        let blocks = ScriptBuilder::new().push_csv_blocks(144).into_script();
+
+        assert_eq!(days, blocks);
+        assert_eq!(days, hex::decode("029000b275").unwrap());
+
+        // Hashlock, key push and opcodes
+        let key: XOnlyPublicKey =
+            "b2d9fb51db445564f1d4e754f644597b11ff191d12c2a582fb598e509cd72421"
+                .parse()
+                .unwrap();
+
+        let script = ScriptBuilder::new()
+            .push_opcode(OP_IF)
+            .push_hash160_lock(&[0xaa; 20])
+            .push_key(&key)
+            .push_opcode(OP_CHECKSIG)
+            .into_script();
+
+        assert_eq!(
+            disassemble(&script).unwrap(),
+            "OP_IF OP_HASH160 <aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa> OP_EQUALVERIFY <b2d9fb51db445564f1d4e754f644597b11ff191d12c2a582fb598e509cd72421> OP_CHECKSIG"
+        );
+    }
+}
@@ -0,0 +1,145 @@
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use brollup::{
+        entry::{entry::Entry, liftdown::Liftdown, liftup::Liftup, transfer::Transfer},
+        taproot::{ControlBlock, TapLeaf, TapRoot},
+        valtype::{
+            account::Account,
+            contract::Contract,
+            maybe_common::MaybeCommon,
+            outpoint::Outpoint,
+            value::{LongVal, ShortVal},
+        },
+    };
+    use musig2::secp256k1::{Parity, PublicKey, XOnlyPublicKey};
+    use serde::{de::DeserializeOwned, Serialize};
+    use std::fmt::Debug;
+
+    fn key() -> XOnlyPublicKey {
+        "b2d9fb51db445564f1d4e754f644597b11ff191d12c2a582fb598e509cd72421"
+            .parse()
+            .unwrap()
+    }
+
+    // Round-trip through both a human-readable and a binary format.
+    fn roundtrip<T: Serialize + DeserializeOwned + PartialEq + Debug>(value: &T) -> String {
+        let json = serde_json::to_string(value).unwrap();
+        assert_eq!(&serde_json::from_str::<T>(&json).unwrap(), value);
+
+        let binary = bincode::serialize(value).unwrap();
+        assert_eq!(&bincode::deserialize::<T>(&binary).unwrap(), value);
+
+        json
+    }
+
+    #[test]
+    fn test_serde_valtypes() {
+        let account = Account::new(key());
+        assert_eq!(
+            roundtrip(&account),
+            r#"{"key":"b2d9fb51db445564f1d4e754f644597b11ff191d12c2a582fb598e509cd72421","account_index":null}"#
+        );
+        roundtrip(&Account::new_compact(key(), 7));
+
+        let contract = Contract::new_compact([0xab; 32], 3);
+        assert_eq!(
+            roundtrip(&contract),
+            r#"{"contract_id":"abababababababababababababababababababababababababababababababab","contract_index":3}"#
+        );
+
+        assert_eq!(roundtrip(&ShortVal::new(23423)), "23423");
+        assert_eq!(roundtrip(&LongVal::new(u64::MAX)), "18446744073709551615");
+
+        // Binary keys are raw bytes, not hex strings.
+        let binary = bincode::serialize(&account).unwrap();
+        assert_eq!(binary[8..40], key().serialize());
+
+        // Invalid keys are rejected.
+        let invalid = r#"{"key":"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff","account_index":null}"#;
+        assert!(serde_json::from_str::<Account>(invalid).is_err());
+
+        let short = r#"{"contract_id":"abab","contract_index":null}"#;
+        assert!(serde_json::from_str::<Contract>(short).is_err());
+    }
+
+    #[test]
+    fn test_serde_entries() {
+        let transfer = Transfer::new(
+            Account::new(key()),
+            0,
+            MaybeCommon::Common(Account::new_compact(key(), 1), 2),
+            MaybeCommon::Uncommon(LongVal::new(1000)),
+        );
+        roundtrip(&transfer);
+
+        let entry = Entry::Transfer(transfer);
+        let json = roundtrip(&entry);
+        assert!(json.starts_with(r#"{"Transfer":{"from":{"key":"b2d9"#));
+
+        let liftup = Liftup::new(
+            Account::new(key()),
+            0,
+            Outpoint::new([0x11; 32], 1),
+            LongVal::new(100_000),
+        );
+        roundtrip(&Entry::Liftup(liftup));
+
+        // Scripts are hex strings.
+        let liftdown = Liftdown::new(
+            Account::new(key()),
+            0,
+            hex::decode("51").unwrap(),
+            LongVal::new(1000),
+        );
+        let json = roundtrip(&Entry::Liftdown(liftdown));
+        assert!(json.contains(r#""spk":"51""#));
+    }
+
+    #[test]
+    fn test_serde_taproot() {
+        let leaf = TapLeaf::new(hex::decode("51").unwrap());
+
+        let json = serde_json::to_string(&leaf).unwrap();
+        assert_eq!(json, r#"{"leaf_version":192,"tap_script":"51"}"#);
+
+        let decoded: TapLeaf = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.hash(), leaf.hash());
+
+        let decoded: TapLeaf = bincode::deserialize(&bincode::serialize(&leaf).unwrap()).unwrap();
+        assert_eq!(decoded.hash(), leaf.hash());
+
+        let inner_key: PublicKey = key().public_key(Parity::Even);
+        let leaves = vec![
+            TapLeaf::new(hex::decode("51").unwrap()),
+            TapLeaf::new(hex::decode("52").unwrap()),
+            TapLeaf::new(hex::decode("53").unwrap()),
+        ];
+
+        for tap_root in [
+            TapRoot::key_path_only(inner_key),
+            TapRoot::key_and_script_path_multi(inner_key, leaves),
+        ] {
+            let json = serde_json::to_string(&tap_root).unwrap();
+            let decoded: TapRoot = serde_json::from_str(&json).unwrap();
+            assert_eq!(decoded.spk().unwrap(), tap_root.spk().unwrap());
+
+            let binary = bincode::serialize(&tap_root).unwrap();
+            let decoded: TapRoot = bincode::deserialize(&binary).unwrap();
+            assert_eq!(decoded.spk().unwrap(), tap_root.spk().unwrap());
+        }
+
+        // An empty tree is rejected.
+        let empty = r#"{"inner_key":"b2d9fb51db445564f1d4e754f644597b11ff191d12c2a582fb598e509cd72421","leaves":[]}"#;
+        assert!(serde_json::from_str::<TapRoot>(empty).is_err());
+
+        let control_block = ControlBlock::new(key(), Parity::Odd, vec![0xcd; 32]);
+
+        let json = serde_json::to_string(&control_block).unwrap();
+        let decoded: ControlBlock = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.to_vec(), control_block.to_vec());
+
+        let binary = bincode::serialize(&control_block).unwrap();
+        let decoded: ControlBlock = bincode::deserialize(&binary).unwrap();
+        assert_eq!(decoded.to_vec(), control_block.to_vec());
+    }
+}
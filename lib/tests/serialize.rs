@@ -1,8 +1,27 @@
 #[cfg(test)]
 mod serialize_tests {
+    use brollup::entry::transfer::Transfer;
     use brollup::serialization::{
-        csv::{to_csv_script_encode, to_n_sequence_encode, CSVFlag}, prefix::Prefix, push::Push
+        bits::{BitReader, BitWriter},
+        cltv::{AbsoluteLock, CLTVError},
+        cpe::{CPEDecodingError, CompactPayloadDecoding, CompactPayloadEncoding},
+        csv::{
+            from_cscript_num, to_cscript_num, to_csv_script_encode, to_n_sequence_encode, CSVError,
+            CSVFlag, RelativeLock,
+        },
+        prefix::{
+            pushes, read_compact_size, read_compact_size_prefixed, read_pushdata, Prefix,
+            PrefixError,
+        },
+        push::{from_multi_pushdata_push, from_multi_witness_push, Push},
+        serialize::{ByteReader, Serialize, SerializeError},
+        timelock::Timelock,
     };
+    use brollup::valtype::{
+        account::Account,
+        value::{LongVal, ShortVal},
+    };
+    use musig2::secp256k1::XOnlyPublicKey;
 
     #[test]
     fn test_prefix_pushdata() {
@@ -443,4 +462,656 @@ mod serialize_tests {
         assert_eq!(chunks[1].clone(), expected_2);
         assert_eq!(chunks[2].clone(), expected_3);
     }
+
+    #[test]
+    fn test_bit_writer_reader() {
+        let mut writer = BitWriter::new();
+
+        // Byte-aligned bitstreams are not padded.
+        writer.write_bytes(&[0xde, 0xad]);
+        assert_eq!(writer.len(), 16);
+        assert_eq!(writer.padding_len(), 0);
+        assert_eq!(writer.to_bytes(), hex::decode("dead").unwrap());
+
+        // b101 followed by 0x0f as a 5-bit integer -> b10101111 b0000000
+        writer.write_uint(0b101, 3);
+        writer.write_uint(0x0f, 5);
+        writer.write_bit(false);
+        assert_eq!(writer.len(), 25);
+        assert_eq!(writer.padding_len(), 7);
+        assert_eq!(writer.to_bytes(), hex::decode("deadaf00").unwrap());
+
+        let bytes = writer.to_bytes();
+        let mut reader = BitReader::from_padded(&bytes, writer.padding_len()).unwrap();
+
+        assert_eq!(reader.read_bytes(2).unwrap(), hex::decode("dead").unwrap());
+        assert_eq!(reader.read_uint(3).unwrap(), 0b101);
+        assert_eq!(reader.read_uint(5).unwrap(), 0x0f);
+        assert!(!reader.read_bit().unwrap());
+        assert!(reader.is_empty());
+        assert_eq!(reader.read_bit(), Err(CPEDecodingError::EndOfBitsError));
+
+        // Padding must be in the range 0..=7.
+        assert!(BitReader::from_padded(&bytes, 8).is_err());
+
+        // Padding bits must be zero.
+        let bytes = hex::decode("dead").unwrap();
+        assert!(BitReader::from_padded(&bytes, 1).is_err());
+        assert!(BitReader::from_padded(&bytes, 0).is_ok());
+
+        // Padding cannot exceed the bitstream.
+        assert!(BitReader::from_padded(&[], 1).is_err());
+    }
+
+    #[test]
+    fn test_cpe_values() {
+        let short_vals = vec![0, 255, 256, 65535, 65536, 16777215, 16777216, 4294967295];
+
+        for value in short_vals {
+            let mut writer = BitWriter::new();
+            ShortVal::new(value).write_cpe(&mut writer);
+
+            let bytes = writer.to_bytes();
+            let mut reader = BitReader::from_padded(&bytes, writer.padding_len()).unwrap();
+
+            assert_eq!(ShortVal::from_cpe(&mut reader, &()).unwrap().value(), value);
+            assert!(reader.is_empty());
+        }
+
+        let long_vals = vec![
+            0,
+            4294967295,
+            4294967296,
+            1099511627775,
+            1099511627776,
+            281474976710655,
+            281474976710656,
+            72057594037927935,
+            72057594037927936,
+            18446744073709551615,
+        ];
+
+        for value in long_vals {
+            let mut writer = BitWriter::new();
+            LongVal::new(value).write_cpe(&mut writer);
+
+            let bytes = writer.to_bytes();
+            let mut reader = BitReader::from_padded(&bytes, writer.padding_len()).unwrap();
+
+            assert_eq!(LongVal::from_cpe(&mut reader, &()).unwrap().value(), value);
+            assert!(reader.is_empty());
+        }
+
+        // b01 -> UInt 16, little-endian: 0x5b7f -> 7f 5b
+        let bits = ShortVal::new(23423).to_cpe();
+        assert_eq!(bits.len(), 18);
+        assert_eq!(bits.to_bytes(), hex::decode("5fd6c0").unwrap());
+    }
+
+    #[test]
+    fn test_cscript_num() {
+        let vectors: Vec<(i64, &str)> = vec![
+            (0, ""),
+            (1, "01"),
+            (-1, "81"),
+            (127, "7f"),
+            (128, "8000"),
+            (-128, "8080"),
+            (255, "ff00"),
+            (256, "0001"),
+            (32767, "ff7f"),
+            (32768, "008000"),
+            (52560, "50cd00"),
+            (4194304, "000040"),
+            (2147483647, "ffffff7f"),
+            (4294967295, "ffffffff00"),
+        ];
+
+        for (value, encoded) in vectors {
+            let encoded = hex::decode(encoded).unwrap();
+
+            assert_eq!(to_cscript_num(value), encoded);
+            assert_eq!(from_cscript_num(&encoded, 5), Ok(value));
+        }
+
+        // Non-minimal encodings
+        assert_eq!(
+            from_cscript_num(&hex::decode("00").unwrap(), 5),
+            Err(CSVError::NonMinimalCScriptNum)
+        );
+        assert_eq!(
+            from_cscript_num(&hex::decode("0100").unwrap(), 5),
+            Err(CSVError::NonMinimalCScriptNum)
+        );
+        assert_eq!(
+            from_cscript_num(&hex::decode("80").unwrap(), 5),
+            Err(CSVError::NonMinimalCScriptNum)
+        );
+
+        // Too long
+        assert_eq!(
+            from_cscript_num(&hex::decode("0000000001").unwrap(), 4),
+            Err(CSVError::CScriptNumOverflow)
+        );
+    }
+
+    #[test]
+    fn test_relative_lock() {
+        // Blocks
+        let lock = RelativeLock::from_days(1).unwrap();
+
+        assert_eq!(lock, RelativeLock::Blocks(144));
+        assert_eq!(lock.n_sequence(), 144);
+        assert_eq!(lock.to_n_sequence_bytes(), hex::decode("90000000").unwrap());
+        assert_eq!(lock.to_cscript_num(), hex::decode("9000").unwrap());
+        assert_eq!(lock.to_csv_script(), hex::decode("029000b275").unwrap());
+
+        assert_eq!(
+            to_n_sequence_encode(CSVFlag::Lock(lock)),
+            to_n_sequence_encode(CSVFlag::CSVDay)
+        );
+        assert_eq!(
+            to_csv_script_encode(CSVFlag::Lock(lock)),
+            to_csv_script_encode(CSVFlag::CSVDay)
+        );
+
+        // Blocks beyond 255 days
+        let lock = RelativeLock::from_days(365).unwrap();
+
+        assert_eq!(lock, RelativeLock::Blocks(52560));
+        assert_eq!(lock.to_csv_script(), hex::decode("0350cd00b275").unwrap());
+        assert_eq!(RelativeLock::from_days(456), None);
+
+        // Single-byte minimal pushes
+        assert_eq!(
+            RelativeLock::Blocks(6).to_csv_script(),
+            hex::decode("56b275").unwrap()
+        );
+
+        // Time: 1 day = 86400 seconds -> 169 intervals (rounded up)
+        let lock = RelativeLock::from_seconds(86400).unwrap();
+
+        assert_eq!(lock, RelativeLock::Time(169));
+        assert_eq!(lock.n_sequence(), 0x004000a9);
+        assert_eq!(lock.to_n_sequence_bytes(), hex::decode("a9004000").unwrap());
+        assert_eq!(lock.to_cscript_num(), hex::decode("a90040").unwrap());
+        assert_eq!(lock.to_csv_script(), hex::decode("03a90040b275").unwrap());
+        assert_eq!(RelativeLock::from_seconds(512 * 65536), None);
+
+        // Parse both forms back
+        for lock in [
+            RelativeLock::Blocks(0),
+            RelativeLock::Blocks(16),
+            RelativeLock::Blocks(65535),
+            RelativeLock::Time(1),
+            RelativeLock::Time(65535),
+        ] {
+            assert_eq!(
+                RelativeLock::from_n_sequence_bytes(&lock.to_n_sequence_bytes()),
+                Ok(lock)
+            );
+            assert_eq!(
+                RelativeLock::from_cscript_num(&lock.to_cscript_num()),
+                Ok(lock)
+            );
+            assert_eq!(
+                RelativeLock::from_csv_script(&lock.to_csv_script()),
+                Ok(lock)
+            );
+        }
+
+        // Disabled and unknown bits
+        assert_eq!(
+            RelativeLock::from_n_sequence(0xffffffff),
+            Err(CSVError::DisabledLock)
+        );
+        assert_eq!(
+            RelativeLock::from_n_sequence(0x00010000),
+            Err(CSVError::UnknownLockBits)
+        );
+        assert_eq!(
+            RelativeLock::from_n_sequence_bytes(&[0x01, 0x00, 0x00]),
+            Err(CSVError::InvalidLength)
+        );
+        assert_eq!(
+            RelativeLock::from_cscript_num(&hex::decode("81").unwrap()),
+            Err(CSVError::NegativeLock)
+        );
+
+        // Non-minimal push
+        assert_eq!(
+            RelativeLock::from_csv_script(&hex::decode("0106b275").unwrap()),
+            Err(CSVError::PushParseError)
+        );
+    }
+
+    #[test]
+    fn test_absolute_lock() {
+        // Height
+        let lock = AbsoluteLock::from_height(840000).unwrap();
+
+        assert_eq!(lock, AbsoluteLock::Height(840000));
+        assert_eq!(
+            lock.to_n_lock_time_bytes(),
+            hex::decode("40d10c00").unwrap()
+        );
+        assert_eq!(lock.to_cscript_num(), hex::decode("40d10c").unwrap());
+        assert_eq!(lock.to_cltv_script(), hex::decode("0340d10cb175").unwrap());
+        assert_eq!(AbsoluteLock::from_height(500_000_000), None);
+
+        // Time
+        let lock = AbsoluteLock::from_timestamp(1700000000).unwrap();
+
+        assert_eq!(lock, AbsoluteLock::Time(1700000000));
+        assert_eq!(
+            lock.to_n_lock_time_bytes(),
+            hex::decode("00f15365").unwrap()
+        );
+        assert_eq!(
+            lock.to_cltv_script(),
+            hex::decode("0400f15365b175").unwrap()
+        );
+        assert_eq!(AbsoluteLock::from_timestamp(499_999_999), None);
+
+        // Timestamps beyond 2^31 need a fifth CScriptNum byte
+        assert_eq!(
+            AbsoluteLock::Time(u32::MAX).to_cltv_script(),
+            hex::decode("05ffffffff00b175").unwrap()
+        );
+
+        // Satisfaction
+        let lock = AbsoluteLock::Height(840000);
+
+        assert!(lock.is_satisfied_by(840000));
+        assert!(lock.is_satisfied_by(840001));
+        assert!(!lock.is_satisfied_by(839999));
+        assert!(!lock.is_satisfied_by(1700000000));
+
+        // Parse both forms back
+        for lock in [
+            AbsoluteLock::Height(0),
+            AbsoluteLock::Height(16),
+            AbsoluteLock::Height(499_999_999),
+            AbsoluteLock::Time(500_000_000),
+            AbsoluteLock::Time(u32::MAX),
+        ] {
+            assert_eq!(
+                AbsoluteLock::from_n_lock_time_bytes(&lock.to_n_lock_time_bytes()),
+                Some(lock)
+            );
+            assert_eq!(
+                AbsoluteLock::from_cscript_num(&lock.to_cscript_num()),
+                Ok(lock)
+            );
+            assert_eq!(
+                AbsoluteLock::from_cltv_script(&lock.to_cltv_script()),
+                Ok(lock)
+            );
+        }
+
+        // CSV fragment is not a CLTV fragment
+        assert_eq!(
+            AbsoluteLock::from_cltv_script(&hex::decode("029000b275").unwrap()),
+            Err(CLTVError::PushParseError)
+        );
+        assert_eq!(
+            AbsoluteLock::from_cscript_num(&hex::decode("81").unwrap()),
+            Err(CLTVError::NegativeLock)
+        );
+
+        // Timelock
+        assert_eq!(
+            Timelock::Absolute(AbsoluteLock::Height(840000)).to_script(),
+            hex::decode("0340d10cb175").unwrap()
+        );
+        assert_eq!(
+            Timelock::Relative(RelativeLock::Blocks(144)).to_script(),
+            to_csv_script_encode(CSVFlag::CSVDay)
+        );
+    }
+
+    #[test]
+    fn test_read_compact_size() {
+        let vectors: Vec<(&str, u64, usize)> = vec![
+            ("00", 0, 1),
+            ("fc", 252, 1),
+            ("fdfd00", 253, 3),
+            ("fdffff", 65535, 3),
+            ("fe00000100", 65536, 5),
+            ("feffffffff", 4294967295, 5),
+            ("ff0000000001000000", 4294967296, 9),
+        ];
+
+        for (encoded, value, len) in vectors {
+            assert_eq!(
+                read_compact_size(&hex::decode(encoded).unwrap()),
+                Ok((value, len))
+            );
+        }
+
+        // Non-canonical encodings
+        for encoded in ["fd0000", "fdfc00", "feffff0000", "ffffffffff00000000"] {
+            assert_eq!(
+                read_compact_size(&hex::decode(encoded).unwrap()),
+                Err(PrefixError::NonCanonicalCompactSize)
+            );
+        }
+
+        // Truncated
+        assert_eq!(
+            read_compact_size(&hex::decode("fd00").unwrap()),
+            Err(PrefixError::UnexpectedEndOfBytes)
+        );
+        assert_eq!(
+            read_compact_size(&[]),
+            Err(PrefixError::UnexpectedEndOfBytes)
+        );
+
+        // Prefixed data roundtrip
+        for data_len in [0, 1, 252, 253, 520, 65536] {
+            let data = vec![0xaa; data_len];
+            let prefixed = data.prefix_compact_size();
+
+            assert_eq!(
+                read_compact_size_prefixed(&prefixed),
+                Ok((data, prefixed.len()))
+            );
+        }
+
+        assert_eq!(
+            read_compact_size_prefixed(&hex::decode("03aabb").unwrap()),
+            Err(PrefixError::UnexpectedEndOfBytes)
+        );
+    }
+
+    #[test]
+    fn test_read_pushdata() {
+        // Minimal pushes roundtrip
+        for data in [
+            vec![0x00],
+            vec![0x01],
+            vec![0x10],
+            vec![0x81],
+            vec![0x11],
+            vec![0xaa; 75],
+            vec![0xaa; 76],
+            vec![0xaa; 255],
+            vec![0xaa; 256],
+            vec![0xaa; 65536],
+        ] {
+            let push = data.prefix_pushdata();
+            assert_eq!(read_pushdata(&push), Ok((data, push.len())));
+        }
+
+        // Non-minimal pushes are read as is
+        assert_eq!(
+            read_pushdata(&hex::decode("0105").unwrap()),
+            Ok((vec![0x05], 2))
+        );
+        assert_eq!(
+            read_pushdata(&hex::decode("4e02000000aabb").unwrap()),
+            Ok((vec![0xaa, 0xbb], 7))
+        );
+
+        // Only the first push is read
+        assert_eq!(
+            read_pushdata(&hex::decode("02aabb51").unwrap()),
+            Ok((vec![0xaa, 0xbb], 3))
+        );
+
+        assert_eq!(
+            read_pushdata(&hex::decode("ac").unwrap()),
+            Err(PrefixError::NonPushOpcode(0xac))
+        );
+        assert_eq!(
+            read_pushdata(&hex::decode("4c02aa").unwrap()),
+            Err(PrefixError::UnexpectedEndOfBytes)
+        );
+        assert_eq!(
+            read_pushdata(&hex::decode("4d01").unwrap()),
+            Err(PrefixError::UnexpectedEndOfBytes)
+        );
+    }
+
+    #[test]
+    fn test_pushes() {
+        let script = hex::decode("0002aabb4f6001ff").unwrap();
+
+        let items: Vec<Vec<u8>> = pushes(&script).map(|push| push.unwrap()).collect();
+
+        assert_eq!(
+            items,
+            vec![
+                vec![0x00],
+                vec![0xaa, 0xbb],
+                vec![0x81],
+                vec![0x10],
+                vec![0xff]
+            ]
+        );
+
+        // Iteration stops at the first malformed push.
+        let script = hex::decode("51ac52").unwrap();
+        let items: Vec<Result<Vec<u8>, PrefixError>> = pushes(&script).collect();
+
+        assert_eq!(
+            items,
+            vec![Ok(vec![0x01]), Err(PrefixError::NonPushOpcode(0xac))]
+        );
+    }
+
+    #[test]
+    fn test_multi_push_rejoin() {
+        for data_len in [0, 1, 80, 81, 520, 521, 1040, 1500] {
+            let data: Vec<u8> = (0..data_len).map(|i| (i % 256) as u8).collect();
+
+            assert_eq!(
+                from_multi_pushdata_push(&data.as_multi_pushdata_push()),
+                Ok(data.clone())
+            );
+            assert_eq!(
+                from_multi_witness_push(&data.as_multi_witness_push(true), true),
+                Ok(data.clone())
+            );
+            assert_eq!(
+                from_multi_witness_push(&data.as_multi_witness_push(false), false),
+                Ok(data.clone())
+            );
+        }
+
+        // Chunks must be 520 bytes long except for the last one.
+        let mut bytes = vec![0xaa; 519].prefix_pushdata();
+        bytes.extend(vec![0xaa; 2].prefix_pushdata());
+
+        assert_eq!(
+            from_multi_pushdata_push(&bytes),
+            Err(PrefixError::ChunkError)
+        );
+
+        // Standard witness chunks are 80 bytes long.
+        let data = vec![0xaa; 100];
+
+        assert_eq!(
+            from_multi_witness_push(&data.as_multi_witness_push(false), true),
+            Err(PrefixError::ChunkError)
+        );
+
+        // Non-minimal push
+        assert_eq!(
+            from_multi_pushdata_push(&hex::decode("0105").unwrap()),
+            Err(PrefixError::ChunkError)
+        );
+    }
+
+    #[test]
+    fn test_transfer_serialize() {
+        let from: XOnlyPublicKey =
+            "b2d9fb51db445564f1d4e754f644597b11ff191d12c2a582fb598e509cd72421"
+                .parse()
+                .unwrap();
+        let to: XOnlyPublicKey = "fe44f87e8dcf65392e213f304bee1e3a31e562bc1061830d6f2e9539496c46f2"
+            .parse()
+            .unwrap();
+
+        let transfer =
+            Transfer::new_uncommon(Account::new(from), 0, Account::new(to), LongVal::new(23423));
+
+        // Version bit, from, nonce, to, 8-byte amount and the fee flag
+        let serialized = transfer.serialize();
+        let serialized_expected = hex::decode("80b2d9fb51db445564f1d4e754f644597b11ff191d12c2a582fb598e509cd7242100000000fe44f87e8dcf65392e213f304bee1e3a31e562bc1061830d6f2e9539496c46f27f5b00000000000000").unwrap();
+
+        assert_eq!(serialized, serialized_expected);
+        assert_eq!(Transfer::from_bytes(&serialized).unwrap(), transfer);
+
+        // Layout predating nonces, still decoded: from, to and a 4-byte amount
+        let legacy = hex::decode("00b2d9fb51db445564f1d4e754f644597b11ff191d12c2a582fb598e509cd72421fe44f87e8dcf65392e213f304bee1e3a31e562bc1061830d6f2e9539496c46f27f5b0000").unwrap();
+        assert_eq!(Transfer::from_bytes(&legacy).unwrap(), transfer);
+
+        // Streaming: read back-to-back transfers.
+        let mut stream = Vec::<u8>::new();
+        transfer.write_to(&mut stream).unwrap();
+        transfer.write_to(&mut stream).unwrap();
+
+        let mut reader = ByteReader::new(stream.as_slice());
+        assert_eq!(Transfer::read_from(&mut reader).unwrap(), transfer);
+        assert_eq!(reader.offset(), 78);
+        assert_eq!(Transfer::read_from(&mut reader).unwrap(), transfer);
+        assert_eq!(reader.expect_end(), Ok(()));
+
+        // Truncated input
+        for len in 0..serialized.len() {
+            assert_eq!(
+                Transfer::from_bytes(&serialized[..len]),
+                Err(SerializeError::UnexpectedEof { offset: len })
+            );
+        }
+
+        // Trailing bytes
+        let mut trailing = serialized.clone();
+        trailing.push(0x00);
+
+        assert_eq!(
+            Transfer::from_bytes(&trailing),
+            Err(SerializeError::TrailingBytes { offset: 78 })
+        );
+
+        // Entry type
+        let mut invalid_type = serialized.clone();
+        invalid_type[0] = 0x01;
+
+        assert_eq!(
+            Transfer::from_bytes(&invalid_type),
+            Err(SerializeError::EntryTypeError {
+                offset: 0,
+                entry_type: 0x01
+            })
+        );
+
+        // The all-0xff x coordinate is not on the curve.
+        let mut invalid_key = serialized.clone();
+        invalid_key[37..69].copy_from_slice(&[0xff; 32]);
+
+        assert_eq!(
+            Transfer::from_bytes(&invalid_key),
+            Err(SerializeError::InvalidKey { offset: 37 })
+        );
+
+        // Arbitrary input must not panic.
+        let mut seed: u32 = 0x2545f491;
+        for _ in 0..1000 {
+            let len = (seed % 80) as usize;
+            let bytes: Vec<u8> = (0..len)
+                .map(|_| {
+                    seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+                    (seed >> 24) as u8
+                })
+                .collect();
+
+            let _ = Transfer::from_bytes(&bytes);
+        }
+    }
+
+    #[test]
+    fn test_transfer_serialize_versioned() {
+        let from: XOnlyPublicKey =
+            "b2d9fb51db445564f1d4e754f644597b11ff191d12c2a582fb598e509cd72421"
+                .parse()
+                .unwrap();
+        let to: XOnlyPublicKey = "fe44f87e8dcf65392e213f304bee1e3a31e562bc1061830d6f2e9539496c46f2"
+            .parse()
+            .unwrap();
+
+        // Amounts beyond 4 bytes
+        let large = Transfer::new_uncommon(
+            Account::new(from),
+            0,
+            Account::new(to),
+            LongVal::new(5_000_000_000),
+        );
+
+        let serialized = large.serialize();
+        assert_eq!(serialized[0], 0x80);
+        assert_eq!(serialized.len(), 1 + 32 + 4 + 32 + 8 + 1);
+        assert_eq!(serialized[69..77], 5_000_000_000u64.to_le_bytes());
+        assert_eq!(serialized[77], 0x00);
+        assert_eq!(Transfer::from_bytes(&serialized).unwrap(), large);
+
+        // The nonce follows the sender.
+        let with_nonce =
+            Transfer::new_uncommon(Account::new(from), 7, Account::new(to), LongVal::new(1000));
+
+        let serialized = with_nonce.serialize();
+        assert_eq!(serialized[0], 0x80);
+        assert_eq!(serialized.len(), 1 + 32 + 4 + 32 + 8 + 1);
+        assert_eq!(serialized[33..37], 7u32.to_le_bytes());
+        assert_eq!(Transfer::from_bytes(&serialized).unwrap(), with_nonce);
+
+        // Fee
+        let mut with_fee =
+            Transfer::new_uncommon(Account::new(from), 0, Account::new(to), LongVal::new(1000));
+        with_fee.set_fee(LongVal::new(10));
+
+        let serialized = with_fee.serialize();
+        assert_eq!(serialized[0], 0x80);
+        assert_eq!(serialized.len(), 1 + 32 + 4 + 32 + 8 + 1 + 8);
+        assert_eq!(serialized[77], 0x01);
+        assert_eq!(Transfer::from_bytes(&serialized).unwrap(), with_fee);
+
+        for len in 0..serialized.len() {
+            assert_eq!(
+                Transfer::from_bytes(&serialized[..len]),
+                Err(SerializeError::UnexpectedEof { offset: len })
+            );
+        }
+
+        // Invalid fee flag
+        let mut invalid_flag = serialized.clone();
+        invalid_flag[77] = 0x02;
+        assert_eq!(
+            Transfer::from_bytes(&invalid_flag),
+            Err(SerializeError::InvalidFlag {
+                offset: 77,
+                flag: 0x02
+            })
+        );
+
+        // A transfer that also fits the legacy layout is accepted in the versioned one.
+        let mut without_fee = serialized[..78].to_vec();
+        without_fee[77] = 0x00;
+        assert_eq!(
+            Transfer::from_bytes(&without_fee).unwrap(),
+            Transfer::new_uncommon(Account::new(from), 0, Account::new(to), LongVal::new(1000))
+        );
+
+        // Unknown bits besides the version bit
+        let mut invalid_type = serialized.clone();
+        invalid_type[0] = 0x81;
+        assert_eq!(
+            Transfer::from_bytes(&invalid_type),
+            Err(SerializeError::EntryTypeError {
+                offset: 0,
+                entry_type: 0x81
+            })
+        );
+    }
 }
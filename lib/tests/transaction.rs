@@ -0,0 +1,478 @@
+#[cfg(test)]
+mod transaction_tests {
+    use brollup::{
+        covenant::CONNECTOR_VALUE,
+        hash::hash_256,
+        serialization::{
+            csv::CSVFlag,
+            serialize::{Serialize, SerializeError},
+        },
+        taproot::{TapLeaf, TapRoot, P2TR},
+        tx::{
+            pool::{PoolTransaction, PoolTransactionError},
+            sighash::{key_path_sighash, tap_sighash, SighashError, SighashType},
+            transaction::{OutPoint, Transaction, TxIn, TxOut},
+        },
+        txo::{
+            connector::Connector,
+            lift::Lift,
+            payload::Payload,
+            projector::{Projector, ProjectorTag},
+        },
+        valtype::outpoint::Outpoint,
+    };
+    use musig2::secp256k1::{Parity, XOnlyPublicKey};
+
+    fn keys() -> (XOnlyPublicKey, XOnlyPublicKey, XOnlyPublicKey) {
+        let key_1: XOnlyPublicKey =
+            "b2d9fb51db445564f1d4e754f644597b11ff191d12c2a582fb598e509cd72421"
+                .parse()
+                .unwrap();
+        let key_2: XOnlyPublicKey =
+            "255ac1b59bafb50b4fead46fd8bf07884a9e23b6cd82a5e348a756b66973082e"
+                .parse()
+                .unwrap();
+        let operator_key: XOnlyPublicKey =
+            "fe44f87e8dcf65392e213f304bee1e3a31e562bc1061830d6f2e9539496c46f2"
+                .parse()
+                .unwrap();
+
+        (key_1, key_2, operator_key)
+    }
+
+    fn payload() -> Payload {
+        let (key_1, key_2, operator_key) = keys();
+
+        Payload::new(
+            vec![key_1, key_2],
+            operator_key,
+            vec![[0x11; 32], [0x22; 32]],
+            25,
+            10,
+            operator_key,
+            [0xaa; 64],
+            [0xbb; 64],
+            vec![],
+        )
+    }
+
+    fn pool_transaction() -> PoolTransaction {
+        let (key_1, key_2, operator_key) = keys();
+
+        PoolTransaction::new(
+            payload(),
+            Projector::new(vec![key_1, key_2], ProjectorTag::VTXOProjector),
+            20_000,
+            Projector::new(vec![key_1, key_2], ProjectorTag::ConnectorProjector),
+            2,
+            operator_key,
+        )
+    }
+
+    #[test]
+    fn test_transaction_serialize() {
+        // Genesis block coinbase
+        let raw = hex::decode("01000000010000000000000000000000000000000000000000000000000000000000000000ffffffff4d04ffff001d0104455468652054696d65732030332f4a616e2f32303039204368616e63656c6c6f72206f6e206272696e6b206f66207365636f6e64206261696c6f757420666f722062616e6b73ffffffff0100f2052a01000000434104678afdb0fe5548271967f1a67130b7105cd6a828e03909a67962e0ea1f61deb649f6bc3f4cef38c4f35504e51ec112de5c384df7ba0b8d578a4c702b6bf11d5fac00000000").unwrap();
+
+        let tx = Transaction::from_bytes(&raw).unwrap();
+        assert_eq!(tx.version(), 1);
+        assert_eq!(tx.inputs().len(), 1);
+        assert_eq!(tx.inputs()[0].sequence(), 0xffffffff);
+        assert_eq!(tx.outputs()[0].value(), 5_000_000_000);
+        assert_eq!(tx.serialize(), raw);
+
+        let mut txid = tx.txid();
+        txid.reverse();
+        assert_eq!(
+            hex::encode(txid),
+            "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b"
+        );
+
+        assert!(Transaction::from_bytes(&raw[..raw.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_transaction_segwit() {
+        let input = TxIn::new_csv(OutPoint::new([0x11; 32], 1), CSVFlag::CSVWeek);
+        assert_eq!(input.sequence(), 1008);
+
+        let spk = hex::decode(format!("5120{}", "22".repeat(32))).unwrap();
+        let mut tx = Transaction::new(2, vec![input], vec![TxOut::new(1000, spk)], 0);
+        let base = tx.serialize();
+        assert!(!tx.has_witness());
+        assert_eq!(tx.txid(), tx.wtxid());
+
+        tx.set_witness(0, vec![vec![0xaa; 64]]).unwrap();
+        assert!(tx.has_witness());
+        assert_eq!(tx.set_witness(1, vec![]), None);
+
+        let expected = [
+            "02000000",
+            // Marker and flag
+            "0001",
+            "01",
+            &"11".repeat(32),
+            "01000000",
+            "00",
+            "f0030000",
+            "01",
+            "e803000000000000",
+            "22",
+            "5120",
+            &"22".repeat(32),
+            // Witness
+            "01",
+            "40",
+            &"aa".repeat(64),
+            "00000000",
+        ]
+        .concat();
+        assert_eq!(hex::encode(tx.serialize()), expected);
+        assert_eq!(tx.serialize_without_witness(), base);
+
+        // Witnesses do not affect the txid.
+        assert_eq!(tx.txid(), hash_256(&base));
+        assert_eq!(tx.wtxid(), hash_256(tx.serialize()));
+        assert_ne!(tx.wtxid(), tx.txid());
+
+        assert_eq!(Transaction::from_bytes(&tx.serialize()).unwrap(), tx);
+        assert_eq!(
+            Transaction::from_bytes(&base).unwrap().inputs()[0].witness(),
+            Vec::<Vec<u8>>::new()
+        );
+
+        // Unknown flag
+        let mut bytes = tx.serialize();
+        bytes[5] = 0x02;
+        assert_eq!(
+            Transaction::from_bytes(&bytes),
+            Err(SerializeError::InvalidFlag {
+                offset: 5,
+                flag: 0x02
+            })
+        );
+
+        // Segwit format without witnesses
+        let mut bytes = base.clone();
+        bytes.splice(4..4, [0x00, 0x01]);
+        bytes.splice(bytes.len() - 4..bytes.len() - 4, [0x00]);
+        assert_eq!(
+            Transaction::from_bytes(&bytes),
+            Err(SerializeError::NonCanonicalEncoding { offset: 4 })
+        );
+    }
+
+    #[test]
+    fn test_pool_transaction() {
+        let (key_1, key_2, operator_key) = keys();
+
+        let pool_tx = pool_transaction()
+            .spend_lift(Outpoint::new([0x02; 32], 3), 50_000)
+            .spend_payload(Outpoint::new([0x01; 32], 0), 330)
+            .spend_lift(Outpoint::new([0x03; 32], 0), 10_000)
+            .fee(1_000);
+
+        let tx = pool_tx.build().unwrap();
+        assert_eq!(tx.version(), 2);
+        assert_eq!(tx.lock_time(), 0);
+
+        // Previous payload first, then lifts in the order they were added.
+        let prevouts: Vec<Outpoint> = tx.inputs().iter().map(|input| input.outpoint()).collect();
+        assert_eq!(
+            prevouts,
+            vec![
+                Outpoint::new([0x01; 32], 0),
+                Outpoint::new([0x02; 32], 3),
+                Outpoint::new([0x03; 32], 0),
+            ]
+        );
+
+        // Payload, VTXO projector, connector projector, operator change.
+        let vtxo_projector = Projector::new(vec![key_1, key_2], ProjectorTag::VTXOProjector);
+        let operator = TapRoot::key_path_only(operator_key.public_key(Parity::Even));
+
+        let outputs = tx.outputs();
+        assert_eq!(outputs.len(), 4);
+        assert_eq!(outputs[0].spk(), payload().spk().unwrap());
+        assert_eq!(outputs[0].value(), 330);
+        assert_eq!(outputs[1].spk(), vtxo_projector.spk().unwrap());
+        assert_eq!(outputs[1].value(), 20_000);
+        assert_eq!(outputs[2].value(), 2 * CONNECTOR_VALUE);
+        assert_eq!(outputs[3].spk(), operator.spk().unwrap());
+        assert_eq!(outputs[3].value(), 60_330 - 20_000 - 900 - 330 - 1_000);
+        assert_eq!(pool_tx.change(), Ok(38_100));
+
+        assert_eq!(Transaction::from_bytes(&tx.serialize()).unwrap(), tx);
+        assert_eq!(pool_tx.txid().unwrap(), tx.txid());
+
+        // Lifts follow the projectors, then bare connectors, then change.
+        let lift_spk = |key| Lift::new_with_operator(key, operator_key).spk().unwrap();
+        let connector = Connector::new_with_operator(key_2, operator_key);
+        let connector_spk = connector.spk().unwrap();
+        let pool_tx = pool_tx
+            .add_connector_output(connector)
+            .add_lift_output(Lift::new_with_operator(key_1, operator_key), 5_000)
+            .add_lift_output(Lift::new_with_operator(key_2, operator_key), 6_000);
+
+        let tx = pool_tx.build().unwrap();
+        let outputs = tx.outputs();
+        assert_eq!(outputs.len(), 7);
+        assert_eq!(outputs[3].spk(), lift_spk(key_1));
+        assert_eq!(outputs[3].value(), 5_000);
+        assert_eq!(outputs[4].spk(), lift_spk(key_2));
+        assert_eq!(outputs[4].value(), 6_000);
+        assert_eq!(outputs[5].spk(), connector_spk);
+        assert_eq!(outputs[5].value(), CONNECTOR_VALUE);
+        assert_eq!(outputs[6].spk(), operator.spk().unwrap());
+        assert_eq!(outputs[6].value(), 38_100 - 5_000 - 6_000 - 450);
+        assert_eq!(pool_tx.change(), Ok(26_650));
+
+        // Change below dust is left to the fee.
+        let tx = pool_transaction()
+            .spend_lift(Outpoint::new([0x02; 32], 0), 21_500)
+            .build()
+            .unwrap();
+        assert_eq!(tx.outputs().len(), 3);
+
+        assert_eq!(
+            pool_transaction().build(),
+            Err(PoolTransactionError::NoInputs)
+        );
+        assert_eq!(
+            pool_transaction()
+                .spend_lift(Outpoint::new([0x02; 32], 0), 21_000)
+                .build(),
+            Err(PoolTransactionError::InsufficientFunds {
+                inputs: 21_000,
+                outputs: 21_230
+            })
+        );
+        assert_eq!(
+            pool_transaction()
+                .spend_lift(Outpoint::new([0x02; 32], 0), u64::MAX)
+                .spend_lift(Outpoint::new([0x03; 32], 0), 1)
+                .build(),
+            Err(PoolTransactionError::ValueOverflow)
+        );
+    }
+
+    // https://github.com/bitcoin/bips/blob/master/bip-0341/wallet-test-vectors.json
+    fn bip341_vector() -> (Transaction, Vec<TxOut>) {
+        let raw = hex::decode("02000000097de20cbff686da83a54981d2b9bab3586f4ca7e48f57f5b55963115f3b334e9c010000000000000000d7b7cab57b1393ace2d064f4d4a2cb8af6def61273e127517d44759b6dafdd990000000000fffffffff8e1f583384333689228c5d28eac13366be082dc57441760d957275419a418420000000000fffffffff0689180aa63b30cb162a73c6d2a38b7eeda2a83ece74310fda0843ad604853b0100000000feffffffaa5202bdf6d8ccd2ee0f0202afbbb7461d9264a25e5bfd3c5a52ee1239e0ba6c0000000000feffffff956149bdc66faa968eb2be2d2faa29718acbfe3941215893a2a3446d32acd050000000000000000000e664b9773b88c09c32cb70a2a3e4da0ced63b7ba3b22f848531bbb1d5d5f4c94010000000000000000e9aa6b8e6c9de67619e6a3924ae25696bb7b694bb677a632a74ef7eadfd4eabf0000000000ffffffffa778eb6a263dc090464cd125c466b5a99667720b1c110468831d058aa1b82af10100000000ffffffff0200ca9a3b000000001976a91406afd46bcdfd22ef94ac122aa11f241244a37ecc88ac807840cb0000000020ac9a87f5594be208f8532db38cff670c450ed2fea8fcdefcc9a663f78bab962b0065cd1d").unwrap();
+
+        let utxos_spent = [
+            (
+                420000000,
+                "512053a1f6e454df1aa2776a2814a721372d6258050de330b3c6d10ee8f4e0dda343",
+            ),
+            (
+                462000000,
+                "5120147c9c57132f6e7ecddba9800bb0c4449251c92a1e60371ee77557b6620f3ea3",
+            ),
+            (
+                294000000,
+                "76a914751e76e8199196d454941c45d1b3a323f1433bd688ac",
+            ),
+            (
+                504000000,
+                "5120e4d810fd50586274face62b8a807eb9719cef49c04177cc6b76a9a4251d5450e",
+            ),
+            (
+                630000000,
+                "512091b64d5324723a985170e4dc5a0f84c041804f2cd12660fa5dec09fc21783605",
+            ),
+            (378000000, "00147dd65592d0ab2fe0d0257d571abf032cd9db93dc"),
+            (
+                672000000,
+                "512075169f4001aa68f15bbed28b218df1d0a62cbbcf1188c6665110c293c907b831",
+            ),
+            (
+                546000000,
+                "5120712447206d7a5238acc7ff53fbe94a3b64539ad291c7cdbc490b7577e4b17df5",
+            ),
+            (
+                588000000,
+                "512077e30a5522dd9f894c3f8b8bd4c4b2cf82ca7da8a3ea6a239655c39c050ab220",
+            ),
+        ];
+
+        let prevouts = utxos_spent
+            .iter()
+            .map(|(value, spk)| TxOut::new(*value, hex::decode(spk).unwrap()))
+            .collect();
+
+        (Transaction::from_bytes(&raw).unwrap(), prevouts)
+    }
+
+    #[test]
+    fn test_tap_sighash() {
+        let (tx, prevouts) = bip341_vector();
+
+        // Key path spends
+        let vectors = [
+            (
+                0,
+                0x03,
+                "2514a6272f85cfa0f45eb907fcb0d121b808ed37c6ea160a5a9046ed5526d555",
+            ),
+            (
+                1,
+                0x83,
+                "325a644af47e8a5a2591cda0ab0723978537318f10e6a63d4eed783b96a71a4d",
+            ),
+            (
+                3,
+                0x01,
+                "bf013ea93474aa67815b1b6cc441d23b64fa310911d991e713cd34c7f5d46669",
+            ),
+            (
+                4,
+                0x00,
+                "4f900a0bae3f1446fd48490c2958b5a023228f01661cda3496a11da502a7f7ef",
+            ),
+            (
+                6,
+                0x02,
+                "15f25c298eb5cdc7eb1d638dd2d45c97c4c59dcaec6679cfc16ad84f30876b85",
+            ),
+            (
+                7,
+                0x82,
+                "cd292de50313804dabe4685e83f923d2969577191a3e1d2882220dca88cbeb10",
+            ),
+            (
+                8,
+                0x81,
+                "cccb739eca6c13a8a89e6e5cd317ffe55669bbda23f2fd37b0f18755e008edd2",
+            ),
+        ];
+
+        for (input_index, hash_type, expected) in vectors {
+            let sighash_type = SighashType::from_byte(hash_type).unwrap();
+            assert_eq!(sighash_type.to_byte(), hash_type);
+
+            let sighash =
+                tap_sighash(&tx, input_index, &prevouts, sighash_type, None, None).unwrap();
+            assert_eq!(hex::encode(sighash), expected);
+        }
+
+        assert_eq!(
+            key_path_sighash(&tx, 4, &prevouts),
+            tap_sighash(&tx, 4, &prevouts, SighashType::Default, None, None)
+        );
+
+        // Script path spends commit to the leaf, annexes are committed to when present.
+        let sighash = |leaf_hash: Option<[u8; 32]>, annex: Option<&[u8]>| {
+            tap_sighash(&tx, 4, &prevouts, SighashType::Default, leaf_hash, annex)
+        };
+
+        let key_path = sighash(None, None).unwrap();
+        let leaf_1 = sighash(Some([0x01; 32]), None).unwrap();
+        let leaf_2 = sighash(Some([0x02; 32]), None).unwrap();
+        assert_ne!(leaf_1, key_path);
+        assert_ne!(leaf_1, leaf_2);
+        assert_ne!(sighash(None, Some(&[0x50])).unwrap(), key_path);
+        assert_ne!(sighash(Some([0x01; 32]), Some(&[0x50])).unwrap(), leaf_1);
+
+        assert_eq!(sighash(None, Some(&[0x51])), Err(SighashError::AnnexError));
+
+        assert_eq!(
+            tap_sighash(&tx, 2, &prevouts, SighashType::Single, None, None),
+            Err(SighashError::SingleWithoutOutput)
+        );
+        assert_eq!(
+            key_path_sighash(&tx, 9, &prevouts),
+            Err(SighashError::InputIndexError)
+        );
+        assert_eq!(
+            key_path_sighash(&tx, 0, &prevouts[1..]),
+            Err(SighashError::PrevoutsMismatch)
+        );
+        assert_eq!(
+            SighashType::from_byte(0x04),
+            Err(SighashError::SighashTypeError(0x04))
+        );
+
+        // With ANYONECANPAY, the output spent by the signed input is enough.
+        for (input_index, hash_type) in [(1, 0x83), (7, 0x82), (8, 0x81)] {
+            let sighash_type = SighashType::from_byte(hash_type).unwrap();
+            let prevout = &prevouts[input_index..input_index + 1];
+            assert_eq!(
+                tap_sighash(&tx, input_index, prevout, sighash_type, None, None),
+                tap_sighash(&tx, input_index, &prevouts, sighash_type, None, None)
+            );
+        }
+        assert_eq!(
+            tap_sighash(
+                &tx,
+                1,
+                &prevouts[..2],
+                SighashType::AllAnyoneCanPay,
+                None,
+                None
+            ),
+            Err(SighashError::PrevoutsMismatch)
+        );
+        assert_eq!(
+            tap_sighash(&tx, 1, &prevouts[1..2], SighashType::All, None, None),
+            Err(SighashError::PrevoutsMismatch)
+        );
+    }
+
+    #[test]
+    fn test_tap_sighash_script_path() {
+        let (tx, prevouts) = bip341_vector();
+
+        // Leaf of the second BIP-341 scriptPubKey vector
+        let leaf = TapLeaf::new(
+            hex::decode("20d85a959b0290bf19bb89ed43c916be835475d013da4b362117393e25a48229b8ac")
+                .unwrap(),
+        );
+        assert_eq!(
+            hex::encode(leaf.hash()),
+            "5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21"
+        );
+
+        // Signature messages of the BIP-341 transaction as if its inputs were spent through the leaf,
+        // from the reference implementation in Bitcoin Core's test framework.
+        let vectors = [
+            (
+                4,
+                0x00,
+                None,
+                "a2889022d272e9735136530b46f73635c2f596b0b44faa89d2f6d685981dedcd",
+            ),
+            (
+                1,
+                0x83,
+                None,
+                "12054fd57a6a8465504ac0265bf755196c358f8e5f2bce41973ea822cbc080bc",
+            ),
+            (
+                7,
+                0x82,
+                Some(vec![0x50, 0xaa]),
+                "bbdf802c26568b45ba1642060664eaafb1c55412071f633469956f3b219af8a3",
+            ),
+            (
+                0,
+                0x03,
+                None,
+                "b31f228a306d5e2049631224a3f25e1c437a255ea7731aa4c6e3b32b97cf02a0",
+            ),
+        ];
+
+        for (input_index, hash_type, annex, expected) in vectors {
+            let sighash = tap_sighash(
+                &tx,
+                input_index,
+                &prevouts,
+                SighashType::from_byte(hash_type).unwrap(),
+                Some(leaf.hash()),
+                annex.as_deref(),
+            )
+            .unwrap();
+            assert_eq!(hex::encode(sighash), expected);
+        }
+    }
+}
@@ -1,16 +1,31 @@
 #[cfg(test)]
 mod txo_tests {
     use brollup::{
-        entry::{entry::Entry, transfer::Transfer},
-        serialization::cpe::CompactPayloadEncoding,
+        entry::{call::Call, compressor::compress_entries, entry::Entry, transfer::Transfer},
+        serialization::{
+            cltv::AbsoluteLock,
+            cpe::{CPEContext, CompactPayloadEncoding},
+            serialize::Serialize,
+            timelock::Timelock,
+        },
         taproot::P2TR,
         txo::{
+            channel::Channel,
             connector::Connector,
             lift::Lift,
+            payload::{Payload, PayloadError, PAYLOAD_VERSION},
             projector::{Projector, ProjectorTag},
+            remote::Remote,
+            self_txo::SelfTXO,
+            trigger::Trigger,
             vtxo::VTXO,
         },
-        valtype::{account::Account, value::ShortVal},
+        valtype::{
+            account::Account,
+            contract::Contract,
+            maybe_common::MaybeCommon,
+            value::{LongVal, ShortVal},
+        },
     };
     use musig2::secp256k1::{self, Parity, PublicKey, XOnlyPublicKey};
 
@@ -47,6 +62,36 @@ mod txo_tests {
         Ok(())
     }
 
+    #[test]
+    fn test_lift_absolute_timelock() -> Result<(), secp256k1::Error> {
+        let self_key: XOnlyPublicKey =
+            "b2d9fb51db445564f1d4e754f644597b11ff191d12c2a582fb598e509cd72421"
+                .parse()
+                .unwrap();
+
+        let mut lift_txo = Lift::new(self_key);
+        lift_txo.set_exit_timelock(Timelock::Absolute(AbsoluteLock::Height(840000)));
+
+        let tap_tree = lift_txo
+            .taproot()?
+            .tree()
+            .expect("lift_txo is not a valid tap_tree");
+
+        let exit_path = tap_tree.leaves()[0].tap_script();
+
+        let exit_path_expected = hex::decode(
+            "0340d10cb17520b2d9fb51db445564f1d4e754f644597b11ff191d12c2a582fb598e509cd72421ac",
+        )
+        .unwrap();
+
+        assert_eq!(exit_path, exit_path_expected);
+
+        // Key path is unaffected, script path commitment changes.
+        assert_ne!(lift_txo.spk()?, Lift::new(self_key).spk()?);
+
+        Ok(())
+    }
+
     #[test]
     fn test_vtxo() -> Result<(), secp256k1::Error> {
         let self_key: XOnlyPublicKey =
@@ -161,12 +206,389 @@ mod txo_tests {
                 .unwrap();
 
         let acct = Account::new(key);
-        let amt = ShortVal::new(23423);
+        let amt = LongVal::new(23423);
 
-        let trs = Transfer::new_uncommon(acct, acct, amt);
+        let trs = Transfer::new_uncommon(acct, 0, acct, amt);
 
         let txo = Entry::Transfer(trs);
 
         println!("bits {}", txo.to_cpe());
     }
+
+    struct TestContext {
+        account_key: XOnlyPublicKey,
+    }
+
+    impl CPEContext for TestContext {
+        fn account_key(&self, account_index: u32) -> Option<XOnlyPublicKey> {
+            match account_index {
+                7 => Some(self.account_key),
+                _ => None,
+            }
+        }
+    }
+
+    #[test]
+    fn test_payload_from_tapscript() -> Result<(), secp256k1::Error> {
+        let key_1: XOnlyPublicKey =
+            "b2d9fb51db445564f1d4e754f644597b11ff191d12c2a582fb598e509cd72421"
+                .parse()
+                .unwrap();
+        let key_2: XOnlyPublicKey =
+            "255ac1b59bafb50b4fead46fd8bf07884a9e23b6cd82a5e348a756b66973082e"
+                .parse()
+                .unwrap();
+        let operator_key: XOnlyPublicKey =
+            "fe44f87e8dcf65392e213f304bee1e3a31e562bc1061830d6f2e9539496c46f2"
+                .parse()
+                .unwrap();
+        let fresh_operator_key: XOnlyPublicKey =
+            "f28c4676022feba41258aeebcd82ec67c73e7b391fae3b702a61cc28ef3a541d"
+                .parse()
+                .unwrap();
+
+        let entries = vec![
+            Entry::Transfer(Transfer::new_uncommon(
+                Account::new(key_1),
+                0,
+                Account::new(key_2),
+                LongVal::new(23423),
+            )),
+            Entry::Transfer(Transfer::new(
+                Account::new_compact(key_2, 7),
+                0,
+                MaybeCommon::Uncommon(Account::new(key_1)),
+                MaybeCommon::Uncommon(LongVal::new(1000)),
+            )),
+        ];
+
+        let payload = Payload::new(
+            vec![key_1, key_2],
+            operator_key,
+            vec![[0x11; 32], [0x22; 32], [0x33; 32]],
+            25,
+            10,
+            fresh_operator_key,
+            [0xaa; 64],
+            [0xbb; 64],
+            entries.clone(),
+        );
+
+        let tap_script = payload.taproot()?.tree().unwrap().leaves()[0].tap_script();
+        let context = TestContext { account_key: key_2 };
+
+        let decoded = Payload::from_tapscript(&tap_script, &context).unwrap();
+
+        assert_eq!(decoded.operator_key(), operator_key);
+        assert_eq!(decoded.hashlocks().len(), 2);
+        assert_eq!(decoded.sats_per_vbyte(), 25);
+        assert_eq!(decoded.liquidity_basis_points(), 10);
+        assert_eq!(decoded.fresh_operator_key(), fresh_operator_key);
+        assert_eq!(decoded.vtxo_projector_agg_sig(), [0xaa; 64]);
+        assert_eq!(decoded.connector_projector_agg_sig(), [0xbb; 64]);
+        assert_eq!(decoded.entries(), entries);
+
+        // Script-path spend witness: stack element, tapscript, control block
+        let control_block = payload.taproot()?.control_block(0)?.to_vec();
+        let witness = vec![vec![0x01; 64], tap_script.clone(), control_block];
+
+        let decoded = Payload::from_witness(&witness, &context).unwrap();
+        assert_eq!(decoded.entries(), entries);
+
+        // Compact references cannot be resolved without context
+        assert!(matches!(
+            Payload::from_tapscript(&tap_script, &()),
+            Err(PayloadError::EntryDecodingError(_))
+        ));
+
+        // Truncated tapscript
+        assert!(Payload::from_tapscript(&tap_script[..100], &context).is_err());
+
+        // Unknown version
+        let version_offset = tap_script
+            .windows(35)
+            .position(|header| header[3..] == fresh_operator_key.serialize())
+            .unwrap();
+        assert_eq!(tap_script[version_offset], PAYLOAD_VERSION);
+
+        let mut unknown_version = tap_script.clone();
+        unknown_version[version_offset] = 0x01;
+        assert!(matches!(
+            Payload::from_tapscript(&unknown_version, &context),
+            Err(PayloadError::VersionError(0x01))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_payload_compressed() -> Result<(), secp256k1::Error> {
+        let key_1: XOnlyPublicKey =
+            "b2d9fb51db445564f1d4e754f644597b11ff191d12c2a582fb598e509cd72421"
+                .parse()
+                .unwrap();
+        let key_2: XOnlyPublicKey =
+            "255ac1b59bafb50b4fead46fd8bf07884a9e23b6cd82a5e348a756b66973082e"
+                .parse()
+                .unwrap();
+        let operator_key: XOnlyPublicKey =
+            "fe44f87e8dcf65392e213f304bee1e3a31e562bc1061830d6f2e9539496c46f2"
+                .parse()
+                .unwrap();
+
+        // Three transfers to key 2 of the same amount, and one back to key 1
+        let mut entries = Vec::<Entry>::new();
+        for nonce in 0..3 {
+            entries.push(Entry::Transfer(Transfer::new_uncommon(
+                Account::new(key_1),
+                nonce,
+                Account::new(key_2),
+                LongVal::new(1000),
+            )));
+        }
+        entries.push(Entry::Transfer(Transfer::new_uncommon(
+            Account::new(key_2),
+            0,
+            Account::new(key_1),
+            LongVal::new(2500),
+        )));
+
+        // Two calls to a contract that starts out with a common index
+        for nonce in 3..5 {
+            entries.push(Entry::Call(Call::new(
+                Account::new(key_1),
+                nonce,
+                MaybeCommon::Common(Contract::new([0x42; 32]), 5),
+                0,
+                vec![],
+                MaybeCommon::Uncommon(ShortVal::new(0)),
+            )));
+        }
+
+        let new_payload = |entries: Vec<Entry>| {
+            Payload::new(
+                vec![key_1, key_2],
+                operator_key,
+                vec![],
+                25,
+                10,
+                operator_key,
+                [0xaa; 64],
+                [0xbb; 64],
+                entries,
+            )
+        };
+
+        let uncompressed = new_payload(entries.clone());
+        let mut payload = new_payload(entries.clone());
+        payload.compress();
+
+        let tap_script = payload.taproot()?.tree().unwrap().leaves()[0].tap_script();
+        let uncompressed_tap_script =
+            uncompressed.taproot()?.tree().unwrap().leaves()[0].tap_script();
+        assert!(tap_script.len() < uncompressed_tap_script.len());
+
+        // The tables travel with the payload; no context is needed to resolve common references.
+        let compressed = compress_entries(&entries);
+        let decoded = Payload::from_tapscript(&tap_script, &()).unwrap();
+        assert_eq!(decoded.common_tables(), compressed.common_tables());
+        assert_eq!(
+            decoded.common_tables().accounts(),
+            vec![Account::new(key_2)]
+        );
+        assert_eq!(
+            decoded.common_tables().long_vals(),
+            vec![LongVal::new(1000)]
+        );
+        assert_eq!(
+            decoded.common_tables().contracts(),
+            vec![Contract::new([0x42; 32])]
+        );
+        assert_eq!(decoded.entries(), compressed.entries());
+
+        // Same values either way
+        for (decoded, entry) in decoded.entries().iter().zip(entries.iter()) {
+            assert_eq!(decoded.serialize(), entry.serialize());
+        }
+
+        // Uncompressed payloads carry empty tables, so common references do not resolve.
+        assert!(Payload::from_tapscript(&uncompressed_tap_script, &()).is_err());
+
+        let entries = entries[..4].to_vec();
+        let uncompressed_tap_script = new_payload(entries.clone())
+            .taproot()?
+            .tree()
+            .unwrap()
+            .leaves()[0]
+            .tap_script();
+        let decoded = Payload::from_tapscript(&uncompressed_tap_script, &()).unwrap();
+        assert!(decoded.common_tables().is_empty());
+        assert_eq!(decoded.entries(), entries);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_channel() -> Result<(), secp256k1::Error> {
+        let self_key: XOnlyPublicKey =
+            "b2d9fb51db445564f1d4e754f644597b11ff191d12c2a582fb598e509cd72421"
+                .parse()
+                .unwrap();
+        let operator_key: XOnlyPublicKey =
+            "fe44f87e8dcf65392e213f304bee1e3a31e562bc1061830d6f2e9539496c46f2"
+                .parse()
+                .unwrap();
+
+        let channel = Channel::new(self_key, operator_key);
+
+        let leaves = channel.taproot()?.tree().unwrap().leaves();
+        assert_eq!(leaves.len(), 128);
+
+        // (Self + Operator) after 141 days
+        let first_expected = hex::decode("02504fb27520b2d9fb51db445564f1d4e754f644597b11ff191d12c2a582fb598e509cd72421ad20fe44f87e8dcf65392e213f304bee1e3a31e562bc1061830d6f2e9539496c46f2ac").unwrap();
+        assert_eq!(leaves[0].tap_script(), first_expected);
+
+        // (Self + Operator) after 14 days
+        let last_expected = hex::decode("02e007b27520b2d9fb51db445564f1d4e754f644597b11ff191d12c2a582fb598e509cd72421ad20fe44f87e8dcf65392e213f304bee1e3a31e562bc1061830d6f2e9539496c46f2ac").unwrap();
+        assert_eq!(leaves[127].tap_script(), last_expected);
+
+        let spk = channel.spk()?;
+        let spk_expected =
+            hex::decode("512040855590db4b525a75c767753f1dc8af3cf467663cbc8d94e822b0f0cd2f183a")
+                .unwrap();
+
+        assert_eq!(spk, spk_expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_payload_tapscript() -> Result<(), secp256k1::Error> {
+        let key_1: XOnlyPublicKey =
+            "b2d9fb51db445564f1d4e754f644597b11ff191d12c2a582fb598e509cd72421"
+                .parse()
+                .unwrap();
+        let key_2: XOnlyPublicKey =
+            "255ac1b59bafb50b4fead46fd8bf07884a9e23b6cd82a5e348a756b66973082e"
+                .parse()
+                .unwrap();
+        let operator_key: XOnlyPublicKey =
+            "fe44f87e8dcf65392e213f304bee1e3a31e562bc1061830d6f2e9539496c46f2"
+                .parse()
+                .unwrap();
+
+        let entries = vec![Entry::Transfer(Transfer::new_uncommon(
+            Account::new(key_1),
+            0,
+            Account::new(key_2),
+            LongVal::new(23423),
+        ))];
+
+        let payload = Payload::new(
+            vec![key_1, key_2],
+            operator_key,
+            vec![[0x11; 32], [0x22; 32], [0x33; 32]],
+            25,
+            10,
+            operator_key,
+            [0xaa; 64],
+            [0xbb; 64],
+            entries,
+        );
+
+        let tap_script = payload.taproot()?.tree().unwrap().leaves()[0].tap_script();
+
+        // Spending conditions, followed by the payload pushes
+        let conditions_expected = hex::decode("63a914e16ff522e9feb3850f3b1d11a0ed15150ac99f4c88a914cfb21d36b8551e32a32a4331a394460d7c43a90a8820fe44f87e8dcf65392e213f304bee1e3a31e562bc1061830d6f2e9539496c46f2ac6702f003b275205873fc3079f2b8d73b93892fd3949c80e2cb3fc2b65e772057b3222e3eff66c4ac68").unwrap();
+        assert_eq!(tap_script[..conditions_expected.len()], conditions_expected);
+
+        let spk = payload.spk()?;
+        let spk_expected =
+            hex::decode("51207c852d0025a65493f05335c1e0cc17e59c9249feeb78f08310da0b3585221e03")
+                .unwrap();
+
+        assert_eq!(spk, spk_expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_trigger() -> Result<(), secp256k1::Error> {
+        let self_key: XOnlyPublicKey =
+            "b2d9fb51db445564f1d4e754f644597b11ff191d12c2a582fb598e509cd72421"
+                .parse()
+                .unwrap();
+
+        let trigger_txo = Trigger::new(self_key);
+
+        let tap_tree = trigger_txo
+            .taproot()?
+            .tree()
+            .expect("trigger_txo is not a valid tap_tree");
+
+        let exit_path = tap_tree.leaves()[0].tap_script();
+
+        let exit_path_expected = hex::decode(
+            "02f003b27520b2d9fb51db445564f1d4e754f644597b11ff191d12c2a582fb598e509cd72421ac",
+        )
+        .unwrap();
+
+        assert_eq!(exit_path, exit_path_expected);
+
+        let spk = trigger_txo.taproot()?.spk()?;
+        let spk_expected =
+            hex::decode("5120e98a29c4288d3fd390af221e132ca62ca7b335b78ba6e56804eb3a2fabe3face")
+                .unwrap();
+
+        assert_eq!(spk, spk_expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_self() -> Result<(), secp256k1::Error> {
+        let self_key: XOnlyPublicKey =
+            "255ac1b59bafb50b4fead46fd8bf07884a9e23b6cd82a5e348a756b66973082e"
+                .parse()
+                .unwrap();
+
+        let self_txo = SelfTXO::new(self_key);
+
+        // No script path involved.
+        assert!(self_txo.taproot()?.tree().is_none());
+
+        let spk = self_txo.taproot()?.spk()?;
+        let spk_expected =
+            hex::decode("5120255ac1b59bafb50b4fead46fd8bf07884a9e23b6cd82a5e348a756b66973082e")
+                .unwrap();
+
+        assert_eq!(spk, spk_expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_remote() -> Result<(), secp256k1::Error> {
+        let remote_key: XOnlyPublicKey =
+            "f28c4676022feba41258aeebcd82ec67c73e7b391fae3b702a61cc28ef3a541d"
+                .parse()
+                .unwrap();
+
+        let remote_txo = Remote::new(remote_key);
+
+        // No script path involved.
+        assert!(remote_txo.taproot()?.tree().is_none());
+
+        let spk = remote_txo.taproot()?.spk()?;
+        let spk_expected =
+            hex::decode("5120f28c4676022feba41258aeebcd82ec67c73e7b391fae3b702a61cc28ef3a541d")
+                .unwrap();
+
+        assert_eq!(spk, spk_expected);
+
+        // Self and Remote outputs of the same key are indistinguishable.
+        assert_eq!(spk, SelfTXO::new(remote_key).spk()?);
+
+        Ok(())
+    }
 }